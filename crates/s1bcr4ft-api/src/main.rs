@@ -3,6 +3,10 @@ use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use s1bcr4ft_core::{
     audit::{AuditAction, AuditLogger},
     backup::BackupManager,
+    config::ConfigLoader,
+    module::{ModuleRegistry, ModuleResolver},
+    package::{PackageManager, SyncOptions},
+    vet::{AuditStore, ModuleAudit},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
@@ -48,9 +52,23 @@ struct ModuleInfo {
     description: String,
 }
 
+#[derive(Debug, Serialize)]
+struct ModulesResponse {
+    modules: Vec<ModuleInfo>,
+    suggestions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListModulesQuery {
+    q: Option<String>,
+}
+
 struct AppState {
     audit_logger: Mutex<AuditLogger>,
     backup_manager: Mutex<BackupManager>,
+    audit_store: Mutex<AuditStore>,
+    audit_store_path: std::path::PathBuf,
+    config_path: std::path::PathBuf,
 }
 
 // Health check endpoint
@@ -70,9 +88,10 @@ async fn get_status() -> impl Responder {
     HttpResponse::Ok().json(ApiResponse::success(status))
 }
 
-// List all modules
-async fn list_modules() -> impl Responder {
-    let modules = vec![
+// List (optionally search) modules, with "did you mean" suggestions when a
+// search query has no match.
+async fn list_modules(query: web::Query<ListModulesQuery>) -> impl Responder {
+    let all_modules = vec![
         ModuleInfo {
             id: "core/base-system".to_string(),
             name: "Base System".to_string(),
@@ -93,7 +112,40 @@ async fn list_modules() -> impl Responder {
         },
     ];
 
-    HttpResponse::Ok().json(ApiResponse::success(modules))
+    let response = match &query.q {
+        Some(q) if !q.is_empty() => {
+            let known_ids: Vec<String> = all_modules.iter().map(|m| m.id.clone()).collect();
+            let q_lower = q.to_lowercase();
+
+            let matched: Vec<ModuleInfo> = all_modules
+                .into_iter()
+                .filter(|m| {
+                    m.id.to_lowercase().contains(&q_lower)
+                        || m.name.to_lowercase().contains(&q_lower)
+                        || m.description.to_lowercase().contains(&q_lower)
+                })
+                .collect();
+
+            let suggestions = if matched.is_empty() {
+                s1bcr4ft_core::suggest_id(q, known_ids.iter().map(String::as_str))
+                    .into_iter()
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            ModulesResponse {
+                modules: matched,
+                suggestions,
+            }
+        }
+        _ => ModulesResponse {
+            modules: all_modules,
+            suggestions: Vec::new(),
+        },
+    };
+
+    HttpResponse::Ok().json(ApiResponse::success(response))
 }
 
 // Get config
@@ -134,6 +186,47 @@ async fn get_audit_log(data: web::Data<AppState>) -> impl Responder {
     }
 }
 
+// Get vet audits
+async fn get_vet_audits(data: web::Data<AppState>) -> impl Responder {
+    let audit_store = data.audit_store.lock().unwrap();
+    HttpResponse::Ok().json(ApiResponse::success(audit_store.audits.clone()))
+}
+
+#[derive(Debug, Deserialize)]
+struct CertifyRequest {
+    module_id: String,
+    criteria: Vec<String>,
+    source_url: String,
+    pinned_hash: String,
+}
+
+// Certify a module as vetted
+async fn certify_vet(req: web::Json<CertifyRequest>, data: web::Data<AppState>) -> impl Responder {
+    let mut audit_store = data.audit_store.lock().unwrap();
+    audit_store.certify(ModuleAudit {
+        module_id: req.module_id.clone(),
+        criteria: req.criteria.clone(),
+        source_url: req.source_url.clone(),
+        pinned_hash: req.pinned_hash.clone(),
+        notes: None,
+    });
+
+    if let Err(e) = audit_store.save(&data.audit_store_path) {
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
+    }
+
+    let audit_logger = data.audit_logger.lock().unwrap();
+    let _ = audit_logger.log(
+        AuditAction::VetCertify,
+        serde_json::json!({"module_id": req.module_id}),
+        true,
+    );
+
+    HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
+        "module_id": req.module_id,
+    })))
+}
+
 #[derive(Debug, Deserialize)]
 struct SyncRequest {
     dry_run: Option<bool>,
@@ -143,18 +236,82 @@ struct SyncRequest {
 async fn sync_system(req: web::Json<SyncRequest>, data: web::Data<AppState>) -> impl Responder {
     let dry_run = req.dry_run.unwrap_or(false);
 
+    let config = match ConfigLoader::load(&data.config_path) {
+        Ok(config) => config,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
+    let modules_dir = data
+        .config_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join("modules");
+    let mut registry = ModuleRegistry::new(modules_dir);
+    if let Err(e) = registry.load_all() {
+        return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()));
+    }
+
+    let resolver = ModuleResolver::with_aliases(&registry, config.module_profiles.clone());
+    let resolved = match resolver.resolve_versions(&config.modules) {
+        Ok(resolved) => resolved,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())),
+    };
+
+    let mut packages = Vec::new();
+    let mut aur_packages = Vec::new();
+    let mut commands = Vec::new();
+    for resolved_module in &resolved {
+        let module = match registry.get(&resolved_module.id) {
+            Some(module) => module,
+            None => {
+                return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(format!(
+                    "Cannot sync module '{}': not found in registry",
+                    resolved_module.id
+                )))
+            }
+        };
+        packages.extend(module.packages.iter().cloned());
+        aur_packages.extend(module.aur_packages.iter().cloned());
+        commands.extend(module.commands.iter().cloned());
+    }
+
+    let options = SyncOptions {
+        dry_run,
+        require_vetted: config.security.require_vetted_packages,
+        ..Default::default()
+    };
+
+    let manager = PackageManager::new();
+    let report = match manager
+        .sync(&packages, &aur_packages, &commands, &options)
+        .await
+    {
+        Ok(report) => report,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(e.to_string()))
+        }
+    };
+
     // Log the sync action
     let audit_logger = data.audit_logger.lock().unwrap();
     let _ = audit_logger.log(
         AuditAction::Sync,
-        serde_json::json!({"dry_run": dry_run}),
-        true,
+        serde_json::json!({
+            "dry_run": dry_run,
+            "packages_installed": report.packages_installed,
+            "packages_failed": report.packages_failed,
+        }),
+        report.packages_failed.is_empty(),
     );
 
     HttpResponse::Ok().json(ApiResponse::success(serde_json::json!({
         "message": if dry_run { "Dry run completed" } else { "Sync completed" },
-        "packages_installed": 0,
-        "duration_secs": 0
+        "packages_installed": report.packages_installed.len(),
+        "packages_failed": report.packages_failed,
+        "commands_executed": report.commands_executed.len(),
+        "duration_secs": report.duration_secs,
     })))
 }
 
@@ -171,15 +328,23 @@ async fn main() -> std::io::Result<()> {
     println!("  GET  /api/config      - Get configuration");
     println!("  GET  /api/backups     - List backups");
     println!("  GET  /api/audit       - Get audit log");
+    println!("  GET  /api/vet         - List vetted module audits");
+    println!("  POST /api/vet/certify - Certify a module as vetted");
     println!("  POST /api/sync        - Sync system");
 
     // Initialize app state
     let audit_logger = AuditLogger::new().expect("Failed to create audit logger");
     let backup_manager = BackupManager::new().expect("Failed to create backup manager");
+    let audit_store_path = s1bcr4ft_core::default_data_dir().join("audits.toml");
+    let audit_store = AuditStore::load(&audit_store_path).expect("Failed to load audit store");
+    let config_path = std::path::PathBuf::from("config.yml");
 
     let app_state = web::Data::new(AppState {
         audit_logger: Mutex::new(audit_logger),
         backup_manager: Mutex::new(backup_manager),
+        audit_store: Mutex::new(audit_store),
+        audit_store_path,
+        config_path,
     });
 
     HttpServer::new(move || {
@@ -197,6 +362,8 @@ async fn main() -> std::io::Result<()> {
             .route("/api/config", web::get().to(get_config))
             .route("/api/backups", web::get().to(list_backups))
             .route("/api/audit", web::get().to(get_audit_log))
+            .route("/api/vet", web::get().to(get_vet_audits))
+            .route("/api/vet/certify", web::post().to(certify_vet))
             .route("/api/sync", web::post().to(sync_system))
     })
     .bind(("0.0.0.0", 8080))?
@@ -1,7 +1,8 @@
 use clap::{Parser, Subcommand};
 use colored::*;
 use s1bcr4ft_core::*;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser)]
 #[command(name = "s1bcr4ft")]
@@ -25,6 +26,14 @@ enum Commands {
         /// Output directory
         #[arg(short, long, default_value = ".")]
         output: PathBuf,
+
+        /// Enable a curated feature's module set (repeatable), e.g. hyprland
+        #[arg(long = "with")]
+        with: Vec<String>,
+
+        /// Disable a previously-enabled feature's module set (repeatable)
+        #[arg(long = "without")]
+        without: Vec<String>,
     },
 
     /// Synchronize system with configuration
@@ -40,6 +49,18 @@ enum Commands {
         /// Force sync even if validation fails
         #[arg(long)]
         force: bool,
+
+        /// Fail instead of regenerating config.lock if resolved modules
+        /// have drifted from it
+        #[arg(long)]
+        locked: bool,
+    },
+
+    /// Regenerate config.lock from the current configuration
+    Lock {
+        /// Configuration file
+        #[arg(short, long, default_value = "config.yml")]
+        config: PathBuf,
     },
 
     /// Show current system status
@@ -55,6 +76,12 @@ enum Commands {
         action: ModuleAction,
     },
 
+    /// Supply-chain vetting for modules (cargo-vet style)
+    Vet {
+        #[command(subcommand)]
+        action: VetAction,
+    },
+
     /// Validate configuration
     Validate {
         /// Configuration file
@@ -92,28 +119,171 @@ enum Commands {
 
     /// System health check
     Health,
+
+    /// Diagnose config/conflict problems and mechanically fix what's safe to
+    Doctor {
+        /// Configuration file
+        #[arg(short, long, default_value = "config.yml")]
+        config: PathBuf,
+
+        /// Preview fixes without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum VetAction {
+    /// Check that every module in config has a satisfying audit
+    Check {
+        /// Configuration file
+        #[arg(short, long, default_value = "config.yml")]
+        config: PathBuf,
+
+        /// Audit store file
+        #[arg(long, default_value = "audits.toml")]
+        store: PathBuf,
+    },
+
+    /// Certify a module as reviewed under the given criteria
+    Certify {
+        /// Module ID
+        module_id: String,
+
+        /// Criteria satisfied by this audit (comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        criteria: Vec<String>,
+
+        /// Exact upstream URL that was reviewed
+        #[arg(long)]
+        source_url: String,
+
+        /// Hash pinned at the time of review
+        #[arg(long)]
+        pinned_hash: String,
+
+        /// Audit store file
+        #[arg(long, default_value = "audits.toml")]
+        store: PathBuf,
+    },
+
+    /// Import a trusted audit set from another maintainer
+    Import {
+        /// URL to fetch the audit set from
+        url: String,
+
+        /// Audit store file
+        #[arg(long, default_value = "audits.toml")]
+        store: PathBuf,
+    },
 }
 
 #[derive(Subcommand)]
 enum ModuleAction {
     /// List all available modules
-    List,
+    List {
+        /// Configuration file (used to locate the sibling modules/ directory)
+        #[arg(short, long, default_value = "config.yml")]
+        config: PathBuf,
+    },
 
     /// Search for modules
     Search {
         /// Search query
         query: String,
+
+        /// Configuration file (used to locate the sibling modules/ directory)
+        #[arg(short, long, default_value = "config.yml")]
+        config: PathBuf,
     },
 
     /// Install a module
     Install {
         /// Module ID
         module_id: String,
+
+        /// Configuration file (used to locate the sibling modules/ directory)
+        #[arg(short, long, default_value = "config.yml")]
+        config: PathBuf,
     },
 }
 
+/// Top-level subcommand names, used to stop a user-defined alias from
+/// shadowing a built-in command.
+const BUILTIN_COMMANDS: &[&str] = &[
+    "init", "sync", "lock", "status", "module", "vet", "validate", "rollback", "audit", "export",
+    "health", "doctor",
+];
+
+/// Pull `--config`/`-c <path>` out of the raw argv, falling back to the
+/// same default clap uses, so aliases can be loaded before clap parses.
+fn config_path_from_args(args: &[String]) -> PathBuf {
+    for (i, arg) in args.iter().enumerate() {
+        if let Some(value) = arg.strip_prefix("--config=") {
+            return PathBuf::from(value);
+        }
+        if (arg == "--config" || arg == "-c") && i + 1 < args.len() {
+            return PathBuf::from(&args[i + 1]);
+        }
+    }
+    PathBuf::from("config.yml")
+}
+
+/// Expand the first positional argument if it names a user-defined alias,
+/// repeating until the head token is a built-in command or an undefined
+/// name. Aliases that would shadow a built-in are never consulted. When an
+/// expansion brings back an alias name that was already expanded, expansion
+/// stops there and that name is passed on to clap as it stands, rather than
+/// looping forever.
+fn expand_aliases(args: Vec<String>, aliases: &HashMap<String, AliasTokens>) -> Vec<String> {
+    if args.len() < 2 {
+        return args;
+    }
+
+    let aliases: HashMap<&String, &AliasTokens> = aliases
+        .iter()
+        .filter(|(name, _)| !BUILTIN_COMMANDS.contains(&name.as_str()))
+        .collect();
+
+    let mut expanded = args;
+    let mut visited = std::collections::HashSet::new();
+
+    loop {
+        let head = expanded[1].clone();
+        if BUILTIN_COMMANDS.contains(&head.as_str()) {
+            break;
+        }
+
+        let Some(tokens) = aliases.get(&head) else {
+            break;
+        };
+
+        if !visited.insert(head.clone()) {
+            eprintln!(
+                "{} alias `{}` recurses on itself; using it literally",
+                "warning:".yellow().bold(),
+                head
+            );
+            break;
+        }
+
+        let mut next = vec![expanded[0].clone()];
+        next.extend(tokens.tokens());
+        next.extend(expanded[2..].iter().cloned());
+        expanded = next;
+    }
+
+    expanded
+}
+
 fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+    let raw_args: Vec<String> = std::env::args().collect();
+    let config_path = config_path_from_args(&raw_args);
+    let aliases = ConfigLoader::load(&config_path)
+        .map(|c| c.aliases)
+        .unwrap_or_default();
+
+    let cli = Cli::parse_from(expand_aliases(raw_args, &aliases));
 
     // Initialize logger
     if cli.verbose {
@@ -127,31 +297,120 @@ fn main() -> anyhow::Result<()> {
     }
 
     match cli.command {
-        Commands::Init { name, output } => cmd_init(name, output),
+        Commands::Init {
+            name,
+            output,
+            with,
+            without,
+        } => cmd_init(name, output, with, without),
         Commands::Sync {
             config,
             dry_run,
             force,
-        } => cmd_sync(config, dry_run, force),
+            locked,
+        } => cmd_sync(config, dry_run, force, locked),
+        Commands::Lock { config } => cmd_lock(config),
         Commands::Status { config } => cmd_status(config),
         Commands::Module { action } => cmd_module(action),
+        Commands::Vet { action } => cmd_vet(action),
         Commands::Validate { config, strict } => cmd_validate(config, strict),
         Commands::Rollback { backup_id } => cmd_rollback(backup_id),
         Commands::Audit { since } => cmd_audit(since),
         Commands::Export { output, encrypted } => cmd_export(output, encrypted),
         Commands::Health => cmd_health(),
+        Commands::Doctor { config, dry_run } => cmd_doctor(config, dry_run),
     }
 }
 
-fn cmd_init(name: String, output: PathBuf) -> anyhow::Result<()> {
+/// A curated, named set of modules (and sensible defaults) that `init
+/// --with <name>` can layer onto a project, Boltzmann-scaffolder style.
+struct Feature {
+    name: &'static str,
+    modules: &'static [&'static str],
+    apply: fn(&mut Config),
+}
+
+const FEATURES: &[Feature] = &[
+    Feature {
+        name: "hyprland",
+        modules: &["linux-optimization/window-managers/hyprland-config"],
+        apply: |_| {},
+    },
+    Feature {
+        name: "rust-dev",
+        modules: &["dev-tools/rust-toolchain"],
+        apply: |_| {},
+    },
+    Feature {
+        name: "red-team",
+        modules: &["red-team/c2-frameworks/sliver-c2"],
+        apply: |config| {
+            config.security.network_isolation = true;
+        },
+    },
+];
+
+fn find_feature(name: &str) -> anyhow::Result<&'static Feature> {
+    FEATURES
+        .iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| {
+            let mut msg = format!("Unknown feature '{}'", name);
+            if let Some(suggestion) =
+                suggest_id(name, FEATURES.iter().map(|f| f.name))
+            {
+                msg.push_str(&format!(" — did you mean `{}`?", suggestion));
+            }
+            anyhow::anyhow!(msg)
+        })
+}
+
+fn cmd_init(
+    name: String,
+    output: PathBuf,
+    with: Vec<String>,
+    without: Vec<String>,
+) -> anyhow::Result<()> {
     println!(
         "{}",
         "🚀 Initializing S1bCr4ft project...".bright_cyan().bold()
     );
 
-    let config = ConfigLoader::new_default(name.clone());
     let config_path = output.join("config.yml");
 
+    let mut config = if config_path.exists() {
+        ConfigLoader::load(&config_path)?
+    } else {
+        ConfigLoader::new_default(name.clone())
+    };
+
+    for feature_name in &without {
+        let feature = find_feature(feature_name)?;
+        config
+            .modules
+            .retain(|m| !feature.modules.contains(&m.as_str()));
+        println!(
+            "{} Removed feature: {}",
+            "➖".bright_cyan(),
+            feature.name.bright_white()
+        );
+    }
+
+    for feature_name in &with {
+        let feature = find_feature(feature_name)?;
+        for module in feature.modules {
+            if !config.modules.iter().any(|m| m == module) {
+                config.modules.push(module.to_string());
+            }
+        }
+        (feature.apply)(&mut config);
+        println!(
+            "{} Enabled feature: {}",
+            "➕".bright_cyan(),
+            feature.name.bright_white()
+        );
+    }
+
     ConfigLoader::save(&config, &config_path)?;
 
     println!(
@@ -169,10 +428,37 @@ fn cmd_init(name: String, output: PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn cmd_sync(config: PathBuf, dry_run: bool, _force: bool) -> anyhow::Result<()> {
+/// Directory modules are loaded from for a given `config.yml` path: a
+/// sibling `modules/` directory.
+fn modules_dir_for(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("modules")
+}
+
+/// Audit store a given `config.yml` path uses: a sibling `audits.toml`.
+fn audits_path_for_config(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("audits.toml")
+}
+
+fn resolve_modules(config: &Config, config_path: &Path) -> anyhow::Result<(ModuleRegistry, Vec<ResolvedModule>)> {
+    let mut registry = ModuleRegistry::new(modules_dir_for(config_path));
+    registry.load_all()?;
+
+    let resolver = ModuleResolver::with_aliases(&registry, config.module_profiles.clone());
+    let resolved = resolver.resolve_versions(&config.modules)?;
+
+    Ok((registry, resolved))
+}
+
+fn cmd_sync(config_path: PathBuf, dry_run: bool, force: bool, locked: bool) -> anyhow::Result<()> {
     println!("{}", "🔄 Synchronizing system...".bright_cyan().bold());
 
-    let config = ConfigLoader::load(&config)?;
+    let config = ConfigLoader::load(&config_path)?;
     println!("  Project: {}", config.name.bright_white().bold());
     println!(
         "  Modules: {}",
@@ -186,12 +472,263 @@ fn cmd_sync(config: PathBuf, dry_run: bool, _force: bool) -> anyhow::Result<()>
         );
     }
 
-    // TODO: Implement actual sync
+    let (registry, resolved) = resolve_modules(&config, &config_path)?;
+
+    let audit_store = AuditStore::load(audits_path_for_config(&config_path))?;
+    let vetter = ModuleVetter::new(
+        &audit_store,
+        &config.security.required_vet_criteria,
+        &config.security.vet_exemptions,
+    );
+    vetter.check(&config.modules)?;
+
+    let lock_path = LockFile::path_for_config(&config_path);
+
+    if lock_path.exists() {
+        let lock_file = LockFile::load(&lock_path)?;
+        if let Err(e) = lock_file.verify(&registry, &resolved) {
+            if locked {
+                return Err(e.into());
+            }
+            println!(
+                "{} {} (regenerating config.lock; pass --locked to fail instead)",
+                "⚠".yellow().bold(),
+                e
+            );
+        }
+    } else if locked {
+        anyhow::bail!(
+            "--locked requires an existing {} but none was found",
+            lock_path.display()
+        );
+    }
+
+    if !dry_run {
+        let lock_file = LockFile::generate(&registry, &resolved)?;
+        lock_file.save(&lock_path)?;
+    }
+
+    let (packages, aur_packages, commands) = collect_module_payload(&registry, &resolved)?;
+
+    let options = SyncOptions {
+        dry_run,
+        force,
+        require_vetted: config.security.require_vetted_packages,
+        ..Default::default()
+    };
+
+    let manager = PackageManager::new();
+    let report = tokio::runtime::Runtime::new()?
+        .block_on(manager.sync(&packages, &aur_packages, &commands, &options))?;
+
+    println!(
+        "\n  Packages installed: {}",
+        report.packages_installed.len().to_string().bright_white()
+    );
+    if !report.commands_executed.is_empty() {
+        println!(
+            "  Commands executed: {}",
+            report.commands_executed.len().to_string().bright_white()
+        );
+    }
+
+    let audit_logger = AuditLogger::new()?;
+    audit_logger.log(
+        AuditAction::Sync,
+        serde_json::json!({
+            "packages_installed": report.packages_installed,
+            "packages_failed": report.packages_failed,
+            "dry_run": dry_run,
+        }),
+        report.packages_failed.is_empty(),
+    )?;
+
+    if !report.packages_failed.is_empty() {
+        println!("\n  {}:", "Packages failed".bright_red().bold());
+        for failed in &report.packages_failed {
+            println!("    - {} ({})", failed.package, failed.reason);
+        }
+        anyhow::bail!(
+            "{} package(s) failed to install",
+            report.packages_failed.len()
+        );
+    }
+
     println!("\n{} Sync complete!", "✓".green().bold());
 
     Ok(())
 }
 
+/// Collect the packages, AUR packages, and commands declared across every
+/// resolved module, in resolution order, for a single `PackageManager::sync`
+/// call covering the whole set.
+fn collect_module_payload(
+    registry: &ModuleRegistry,
+    resolved: &[ResolvedModule],
+) -> anyhow::Result<(Vec<String>, Vec<String>, Vec<String>)> {
+    let mut packages = Vec::new();
+    let mut aur_packages = Vec::new();
+    let mut commands = Vec::new();
+
+    for resolved_module in resolved {
+        let module = registry.get(&resolved_module.id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot sync module '{}': not found in registry",
+                resolved_module.id
+            )
+        })?;
+
+        packages.extend(module.packages.iter().cloned());
+        aur_packages.extend(module.aur_packages.iter().cloned());
+        commands.extend(module.commands.iter().cloned());
+    }
+
+    Ok((packages, aur_packages, commands))
+}
+
+fn cmd_lock(config_path: PathBuf) -> anyhow::Result<()> {
+    println!("{}", "🔒 Regenerating config.lock...".bright_cyan().bold());
+
+    let config = ConfigLoader::load(&config_path)?;
+    let (registry, resolved) = resolve_modules(&config, &config_path)?;
+
+    let lock_file = LockFile::generate(&registry, &resolved)?;
+    let lock_path = LockFile::path_for_config(&config_path);
+    lock_file.save(&lock_path)?;
+
+    println!(
+        "\n{} Wrote {}",
+        "✓".green().bold(),
+        lock_path.display().to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+fn cmd_doctor(config_path: PathBuf, dry_run: bool) -> anyhow::Result<()> {
+    println!("{}", "🩺 Diagnosing configuration...".bright_cyan().bold());
+
+    let mut config = ConfigLoader::load(&config_path)?;
+    let registry = load_registry(&config_path)?;
+    let resolver = ModuleResolver::with_aliases(&registry, config.module_profiles.clone());
+
+    let report = resolver.diagnose_and_fix(&mut config, &config_path, dry_run)?;
+
+    if report.fixes.is_empty() {
+        println!("\n{} No fixable problems found.", "✓".green().bold());
+    } else {
+        let verb = if dry_run { "Would fix" } else { "Fixed" };
+        println!("\n  {}:", verb.bright_yellow().bold());
+        for fix in &report.fixes {
+            println!("    - {} ({})", fix.description, fix.reason);
+        }
+    }
+
+    if !report.unresolved.is_empty() {
+        println!("\n  {}:", "Needs manual attention".bright_red().bold());
+        for issue in &report.unresolved {
+            println!("    - {}", issue);
+        }
+    }
+
+    Ok(())
+}
+
+fn cmd_vet(action: VetAction) -> anyhow::Result<()> {
+    match action {
+        VetAction::Check { config, store } => cmd_vet_check(config, store),
+        VetAction::Certify {
+            module_id,
+            criteria,
+            source_url,
+            pinned_hash,
+            store,
+        } => cmd_vet_certify(module_id, criteria, source_url, pinned_hash, store),
+        VetAction::Import { url, store } => cmd_vet_import(url, store),
+    }
+}
+
+fn cmd_vet_check(config_path: PathBuf, store_path: PathBuf) -> anyhow::Result<()> {
+    println!("{}", "🔎 Checking module vetting status...".bright_cyan().bold());
+
+    let config = ConfigLoader::load(&config_path)?;
+    let audit_store = AuditStore::load(&store_path)?;
+    let vetter = ModuleVetter::new(
+        &audit_store,
+        &config.security.required_vet_criteria,
+        &config.security.vet_exemptions,
+    );
+
+    match vetter.check(&config.modules) {
+        Ok(()) => {
+            println!("\n{} All modules satisfy required vet criteria!", "✓".green().bold());
+            Ok(())
+        }
+        Err(e) => {
+            println!("\n{} {}", "✗".red().bold(), e);
+            Err(e.into())
+        }
+    }
+}
+
+fn cmd_vet_certify(
+    module_id: String,
+    criteria: Vec<String>,
+    source_url: String,
+    pinned_hash: String,
+    store_path: PathBuf,
+) -> anyhow::Result<()> {
+    println!(
+        "{} Certifying module: {}",
+        "✅".bright_cyan(),
+        module_id.bright_white()
+    );
+
+    let mut audit_store = AuditStore::load(&store_path)?;
+    audit_store.certify(ModuleAudit {
+        module_id: module_id.clone(),
+        criteria,
+        source_url,
+        pinned_hash,
+        notes: None,
+    });
+    audit_store.save(&store_path)?;
+
+    let audit_logger = AuditLogger::new()?;
+    audit_logger.log(
+        AuditAction::VetCertify,
+        serde_json::json!({ "module_id": module_id }),
+        true,
+    )?;
+
+    println!(
+        "\n{} Wrote {}",
+        "✓".green().bold(),
+        store_path.display().to_string().bright_white()
+    );
+
+    Ok(())
+}
+
+fn cmd_vet_import(url: String, store_path: PathBuf) -> anyhow::Result<()> {
+    println!("{} Importing audit set from: {}", "⬇".bright_cyan(), url.bright_white());
+
+    #[cfg(feature = "remote-modules")]
+    {
+        let mut audit_store = AuditStore::load(&store_path)?;
+        audit_store.import_from_url(&url)?;
+        audit_store.save(&store_path)?;
+        println!("\n{} Imported audits into {}", "✓".green().bold(), store_path.display());
+        Ok(())
+    }
+
+    #[cfg(not(feature = "remote-modules"))]
+    {
+        let _ = store_path;
+        anyhow::bail!("Importing remote audit sets requires the `remote-modules` feature")
+    }
+}
+
 fn cmd_status(config: PathBuf) -> anyhow::Result<()> {
     println!("{}", "📊 System Status".bright_cyan().bold());
 
@@ -203,47 +740,99 @@ fn cmd_status(config: PathBuf) -> anyhow::Result<()> {
         config.modules.len().to_string().bright_white()
     );
 
+    if !config.aliases.is_empty() {
+        println!("\n  {}", "Aliases:".bright_yellow().bold());
+        let mut names: Vec<&String> = config.aliases.keys().collect();
+        names.sort();
+        for name in names {
+            let tokens = config.aliases[name].tokens().join(" ");
+            println!("    {} -> {}", name.bright_white().bold(), tokens);
+        }
+    }
+
     Ok(())
 }
 
+fn load_registry(config_path: &Path) -> anyhow::Result<ModuleRegistry> {
+    let mut registry = ModuleRegistry::new(modules_dir_for(config_path));
+    registry.load_all()?;
+    Ok(registry)
+}
+
 fn cmd_module(action: ModuleAction) -> anyhow::Result<()> {
     match action {
-        ModuleAction::List => {
+        ModuleAction::List { config } => {
             println!("{}", "📦 Available Modules".bright_cyan().bold());
-            println!("\n  (Module listing not yet implemented)");
+            let registry = load_registry(&config)?;
+            let mut modules = registry.list();
+            modules.sort_by(|a, b| a.id.cmp(&b.id));
+
+            if modules.is_empty() {
+                println!(
+                    "\n  (no modules found under {})",
+                    modules_dir_for(&config).display()
+                );
+            } else {
+                for module in modules {
+                    println!("  {} - {}", module.id.bright_white().bold(), module.description);
+                }
+            }
         }
-        ModuleAction::Search { query } => {
+        ModuleAction::Search { query, config } => {
             println!(
                 "{} Searching for: {}",
                 "🔍".bright_cyan(),
                 query.bright_white()
             );
-            println!("\n  (Module search not yet implemented)");
+            let registry = load_registry(&config)?;
+            let results = registry.search(&query);
+
+            if results.is_empty() {
+                println!("\n  No modules matched '{}'", query);
+                if let Some(suggestion) = registry.suggest(&query) {
+                    println!("  did you mean `{}`?", suggestion.bright_white());
+                }
+            } else {
+                for module in results {
+                    println!("  {} - {}", module.id.bright_white().bold(), module.description);
+                }
+            }
         }
-        ModuleAction::Install { module_id } => {
+        ModuleAction::Install { module_id, config } => {
             println!(
                 "{} Installing module: {}",
                 "📥".bright_cyan(),
                 module_id.bright_white()
             );
+            let registry = load_registry(&config)?;
+
+            if registry.get(&module_id).is_none() {
+                println!("\n{} Module '{}' not found", "✗".red().bold(), module_id);
+                if let Some(suggestion) = registry.suggest(&module_id) {
+                    println!("  did you mean `{}`?", suggestion.bright_white());
+                }
+                anyhow::bail!("Module '{}' not found", module_id);
+            }
+
             println!("\n  (Module installation not yet implemented)");
         }
     }
     Ok(())
 }
 
-fn cmd_validate(config: PathBuf, _strict: bool) -> anyhow::Result<()> {
+fn cmd_validate(config_path: PathBuf, _strict: bool) -> anyhow::Result<()> {
     println!("{}", "✓ Validating configuration...".bright_cyan().bold());
 
-    let config = ConfigLoader::load(&config)?;
-    let errors = validation::ConfigValidator::validate(&config)?;
+    let source = std::fs::read_to_string(&config_path)?;
+    let config = ConfigLoader::load(&config_path)?;
+    let errors = validation::ConfigValidator::validate(&config, &source, &config_path.display().to_string());
 
     if errors.is_empty() {
         println!("\n{} Configuration is valid!", "✓".green().bold());
     } else {
         println!("\n{} Validation errors:", "✗".red().bold());
         for error in errors {
-            println!("  • {}: {}", error.field.bright_yellow(), error.message);
+            println!("{:?}", miette::Report::new(error));
         }
     }
 
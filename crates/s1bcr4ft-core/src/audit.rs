@@ -1,10 +1,16 @@
 use crate::error::{Result, S1bCr4ftError};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 
+/// `prev_hash` for the very first entry in a chain, since there's no
+/// earlier entry to point to.
+const GENESIS_HASH: &str =
+    "sha256-0000000000000000000000000000000000000000000000000000000000000000";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditEntry {
     pub timestamp: DateTime<Utc>,
@@ -12,6 +18,22 @@ pub struct AuditEntry {
     pub user: String,
     pub details: serde_json::Value,
     pub success: bool,
+
+    /// The previous entry's `entry_hash`, or [`GENESIS_HASH`] for the first
+    /// entry in the chain (or in a rotated log, the checkpoint left behind
+    /// by the entries archived out of it).
+    #[serde(default = "genesis_hash")]
+    pub prev_hash: String,
+
+    /// `sha256(prev_hash || canonical_json(timestamp, action, user, details, success))`,
+    /// chaining this entry to every entry before it so an edited or
+    /// reordered log line is detectable by [`AuditLogger::verify_chain`].
+    #[serde(default)]
+    pub entry_hash: String,
+}
+
+fn genesis_hash() -> String {
+    GENESIS_HASH.to_string()
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -25,6 +47,7 @@ pub enum AuditAction {
     BackupRestore,
     ModuleAdd,
     ModuleRemove,
+    VetCertify,
 }
 
 impl AuditAction {
@@ -39,6 +62,7 @@ impl AuditAction {
             AuditAction::BackupRestore => "backup_restore",
             AuditAction::ModuleAdd => "module_add",
             AuditAction::ModuleRemove => "module_remove",
+            AuditAction::VetCertify => "vet_certify",
         }
     }
 }
@@ -83,16 +107,7 @@ impl AuditLogger {
         success: bool,
     ) -> Result<()> {
         let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
-
-        let entry = AuditEntry {
-            timestamp: Utc::now(),
-            action: action.as_str().to_string(),
-            user,
-            details,
-            success,
-        };
-
-        self.write_entry(&entry)
+        self.log_chained(action.as_str().to_string(), user, details, success)
     }
 
     /// Log a custom action
@@ -103,18 +118,151 @@ impl AuditLogger {
         success: bool,
     ) -> Result<()> {
         let user = std::env::var("USER").unwrap_or_else(|_| "unknown".to_string());
+        self.log_chained(action.to_string(), user, details, success)
+    }
+
+    /// Build an entry chained onto the current last entry (or the chain's
+    /// checkpoint, if the log was rotated) and append it.
+    fn log_chained(
+        &self,
+        action: String,
+        user: String,
+        details: serde_json::Value,
+        success: bool,
+    ) -> Result<()> {
+        let timestamp = Utc::now();
+        let prev_hash = self.last_entry_hash()?;
+        let entry_hash =
+            Self::compute_entry_hash(&prev_hash, &timestamp, &action, &user, &details, success)?;
 
         let entry = AuditEntry {
-            timestamp: Utc::now(),
-            action: action.to_string(),
+            timestamp,
+            action,
             user,
             details,
             success,
+            prev_hash,
+            entry_hash,
         };
 
         self.write_entry(&entry)
     }
 
+    /// `entry_hash` of the last line currently in the log, or the chain's
+    /// checkpoint/[`GENESIS_HASH`] if the log is empty.
+    fn last_entry_hash(&self) -> Result<String> {
+        if !self.log_file.exists() {
+            return self.checkpoint_hash();
+        }
+
+        let file = fs::File::open(&self.log_file)
+            .map_err(|e| S1bCr4ftError::audit(format!("Failed to open audit log: {}", e)))?;
+        let reader = BufReader::new(file);
+
+        let mut last_hash = None;
+        for line in reader.lines() {
+            let line =
+                line.map_err(|e| S1bCr4ftError::audit(format!("Failed to read line: {}", e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: AuditEntry = serde_json::from_str(&line).map_err(|e| {
+                S1bCr4ftError::audit(format!("Failed to parse audit entry: {}", e))
+            })?;
+            last_hash = Some(entry.entry_hash);
+        }
+
+        match last_hash {
+            Some(hash) => Ok(hash),
+            None => self.checkpoint_hash(),
+        }
+    }
+
+    /// `sha256(prev_hash || canonical_json(timestamp, action, user, details, success))`.
+    /// The canonical form is a `serde_json` object, whose default (non
+    /// order-preserving) map serializes keys in sorted order, so the same
+    /// fields always hash the same way regardless of construction order.
+    fn compute_entry_hash(
+        prev_hash: &str,
+        timestamp: &DateTime<Utc>,
+        action: &str,
+        user: &str,
+        details: &serde_json::Value,
+        success: bool,
+    ) -> Result<String> {
+        let canonical = serde_json::json!({
+            "timestamp": timestamp,
+            "action": action,
+            "user": user,
+            "details": details,
+            "success": success,
+        });
+        let canonical_json = serde_json::to_string(&canonical)?;
+
+        let mut hasher = Sha256::new();
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(canonical_json.as_bytes());
+        Ok(format!("sha256-{:x}", hasher.finalize()))
+    }
+
+    fn checkpoint_file(&self) -> PathBuf {
+        self.log_file.with_extension("log.checkpoint")
+    }
+
+    /// The hash the live log's chain should start from: the checkpoint left
+    /// by the last `rotate_log` call, or [`GENESIS_HASH`] if the log has
+    /// never been rotated.
+    fn checkpoint_hash(&self) -> Result<String> {
+        let checkpoint_file = self.checkpoint_file();
+        if !checkpoint_file.exists() {
+            return Ok(GENESIS_HASH.to_string());
+        }
+
+        fs::read_to_string(&checkpoint_file)
+            .map(|s| s.trim().to_string())
+            .map_err(|e| {
+                S1bCr4ftError::audit(format!("Failed to read audit chain checkpoint: {}", e))
+            })
+    }
+
+    /// Re-read the log and recompute each entry's hash, reporting the first
+    /// entry whose `prev_hash` doesn't match the entry before it or whose
+    /// `entry_hash` doesn't match its own contents (evidence of a tampered
+    /// or reordered log line).
+    pub fn verify_chain(&self) -> Result<()> {
+        let mut expected_prev = self.checkpoint_hash()?;
+
+        for (index, entry) in self.get_entries(None)?.iter().enumerate() {
+            if entry.prev_hash != expected_prev {
+                return Err(S1bCr4ftError::audit(format!(
+                    "Audit chain broken at entry {}: expected prev_hash '{}', found '{}'",
+                    index, expected_prev, entry.prev_hash
+                )));
+            }
+
+            let recomputed = Self::compute_entry_hash(
+                &entry.prev_hash,
+                &entry.timestamp,
+                &entry.action,
+                &entry.user,
+                &entry.details,
+                entry.success,
+            )?;
+
+            if recomputed != entry.entry_hash {
+                return Err(S1bCr4ftError::audit(format!(
+                    "Audit chain broken at entry {}: entry_hash does not match its contents \
+                     (tampered or reordered)",
+                    index
+                )));
+            }
+
+            expected_prev = entry.entry_hash.clone();
+        }
+
+        Ok(())
+    }
+
     fn write_entry(&self, entry: &AuditEntry) -> Result<()> {
         let json = serde_json::to_string(entry)
             .map_err(|e| S1bCr4ftError::audit(format!("Failed to serialize audit entry: {}", e)))?;
@@ -220,6 +368,15 @@ impl AuditLogger {
             self.write_entry(entry)?;
         }
 
+        // Carry the first kept entry's prev_hash forward as a checkpoint, so
+        // the live segment's chain can be verified without the archive it
+        // was split from.
+        if let Some(first_kept) = to_keep.first() {
+            fs::write(self.checkpoint_file(), &first_kept.prev_hash).map_err(|e| {
+                S1bCr4ftError::audit(format!("Failed to write audit chain checkpoint: {}", e))
+            })?;
+        }
+
         let archived_count = entries.len() - max_entries;
         log::info!("Archived {} audit entries", archived_count);
 
@@ -284,4 +441,66 @@ mod tests {
         let sync_entries = logger.get_entries_by_action(AuditAction::Sync).unwrap();
         assert_eq!(sync_entries.len(), 1);
     }
+
+    #[test]
+    fn test_chain_links_entries_and_verifies() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let logger = AuditLogger::with_file(temp_file.path()).unwrap();
+
+        logger.log(AuditAction::Sync, json!({}), true).unwrap();
+        logger
+            .log(AuditAction::BackupCreate, json!({}), true)
+            .unwrap();
+
+        let entries = logger.get_entries(None).unwrap();
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+        assert_eq!(entries[1].prev_hash, entries[0].entry_hash);
+        assert_ne!(entries[0].entry_hash, entries[1].entry_hash);
+
+        logger.verify_chain().unwrap();
+    }
+
+    #[test]
+    fn test_verify_chain_detects_tampering() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let logger = AuditLogger::with_file(temp_file.path()).unwrap();
+
+        logger.log(AuditAction::Sync, json!({"packages": ["a"]}), true).unwrap();
+        logger
+            .log(AuditAction::BackupCreate, json!({}), true)
+            .unwrap();
+
+        let mut entries = logger.get_entries(None).unwrap();
+        entries[0].details = json!({"packages": ["a", "b"]});
+
+        let tampered = entries
+            .iter()
+            .map(|e| serde_json::to_string(e).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        fs::write(temp_file.path(), tampered + "\n").unwrap();
+
+        let err = logger.verify_chain().unwrap_err();
+        assert!(err.to_string().contains("entry 0"));
+    }
+
+    #[test]
+    fn test_rotate_log_carries_checkpoint_forward() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let logger = AuditLogger::with_file(temp_file.path()).unwrap();
+
+        for _ in 0..5 {
+            logger.log(AuditAction::Sync, json!({}), true).unwrap();
+        }
+
+        let archived = logger.rotate_log(2).unwrap();
+        assert_eq!(archived, 3);
+
+        logger
+            .log(AuditAction::BackupCreate, json!({}), true)
+            .unwrap();
+
+        logger.verify_chain().unwrap();
+        assert_eq!(logger.get_entries(None).unwrap().len(), 3);
+    }
 }
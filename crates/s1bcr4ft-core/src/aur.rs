@@ -0,0 +1,288 @@
+//! Native fallback for installing AUR packages without `paru`/`yay`: query
+//! the AUR RPC for each package's dependencies, clone its git repo into a
+//! cache directory under the data dir, and build it with `makepkg`. Used by
+//! [`crate::package::PackageManager`] when the detected helper is
+//! [`crate::package::PackageHelper::Makepkg`].
+
+use crate::error::{Result, S1bCr4ftError};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// A single AUR package's declared dependencies.
+#[derive(Debug, Clone, Default)]
+pub struct AurPackageInfo {
+    pub name: String,
+    pub depends: Vec<String>,
+    pub make_depends: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AurRpcResponse {
+    results: Vec<AurRpcResult>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct AurRpcResult {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "Depends", default)]
+    depends: Vec<String>,
+    #[serde(rename = "MakeDepends", default)]
+    make_depends: Vec<String>,
+}
+
+/// Query the AUR RPC `info` endpoint for `name`'s declared dependencies.
+#[cfg(feature = "remote-modules")]
+pub fn fetch_info(name: &str) -> Result<AurPackageInfo> {
+    let url = format!(
+        "https://aur.archlinux.org/rpc/?v=5&type=info&arg[]={}",
+        name
+    );
+    let response: AurRpcResponse = reqwest::blocking::get(&url)?.json()?;
+
+    let result = response.results.into_iter().next().ok_or_else(|| {
+        S1bCr4ftError::package(format!("Package '{}' not found on AUR", name))
+    })?;
+
+    Ok(AurPackageInfo {
+        name: result.name,
+        depends: strip_version_constraints(result.depends),
+        make_depends: strip_version_constraints(result.make_depends),
+    })
+}
+
+/// Stub used when the `remote-modules` feature is disabled; native AUR
+/// installs need network access to resolve dependencies.
+#[cfg(not(feature = "remote-modules"))]
+pub fn fetch_info(_name: &str) -> Result<AurPackageInfo> {
+    Err(S1bCr4ftError::package(
+        "Native AUR installs require the remote-modules feature",
+    ))
+}
+
+/// AUR dependency strings carry an optional version constraint, e.g.
+/// `glibc>=2.38`; keep just the package name.
+fn strip_version_constraints(deps: Vec<String>) -> Vec<String> {
+    deps.into_iter()
+        .map(|dep| {
+            dep.split(['<', '>', '='])
+                .next()
+                .unwrap_or(&dep)
+                .to_string()
+        })
+        .collect()
+}
+
+/// A resolved build: repo packages to install with `pacman -S` first, and
+/// AUR packages in the order they must be built so prerequisites are ready.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AurBuildPlan {
+    pub repo_deps: Vec<String>,
+    pub aur_order: Vec<String>,
+}
+
+/// Resolve the build order for `packages`. `fetch` looks up an AUR
+/// package's dependencies; `is_repo_package` tells repo packages apart from
+/// AUR ones. Cycle detection mirrors [`crate::module::ModuleResolver`]'s
+/// visiting/visited sets.
+pub fn resolve_build_order(
+    packages: &[String],
+    fetch: &dyn Fn(&str) -> Result<AurPackageInfo>,
+    is_repo_package: &dyn Fn(&str) -> bool,
+) -> Result<AurBuildPlan> {
+    let mut plan = AurBuildPlan::default();
+    let mut visiting = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut info_cache: HashMap<String, AurPackageInfo> = HashMap::new();
+
+    for package in packages {
+        visit(
+            package,
+            fetch,
+            is_repo_package,
+            &mut visiting,
+            &mut visited,
+            &mut info_cache,
+            &mut plan,
+        )?;
+    }
+
+    Ok(plan)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn visit(
+    name: &str,
+    fetch: &dyn Fn(&str) -> Result<AurPackageInfo>,
+    is_repo_package: &dyn Fn(&str) -> bool,
+    visiting: &mut HashSet<String>,
+    visited: &mut HashSet<String>,
+    info_cache: &mut HashMap<String, AurPackageInfo>,
+    plan: &mut AurBuildPlan,
+) -> Result<()> {
+    if visited.contains(name) {
+        return Ok(());
+    }
+
+    if is_repo_package(name) {
+        if !plan.repo_deps.iter().any(|p| p == name) {
+            plan.repo_deps.push(name.to_string());
+        }
+        visited.insert(name.to_string());
+        return Ok(());
+    }
+
+    if visiting.contains(name) {
+        return Err(S1bCr4ftError::Dependency(format!(
+            "Circular AUR dependency detected: {}",
+            name
+        )));
+    }
+
+    visiting.insert(name.to_string());
+
+    let info = match info_cache.get(name) {
+        Some(info) => info.clone(),
+        None => {
+            let info = fetch(name)?;
+            info_cache.insert(name.to_string(), info.clone());
+            info
+        }
+    };
+
+    for dep in info.depends.iter().chain(info.make_depends.iter()) {
+        visit(
+            dep,
+            fetch,
+            is_repo_package,
+            visiting,
+            visited,
+            info_cache,
+            plan,
+        )?;
+    }
+
+    visiting.remove(name);
+    visited.insert(name.to_string());
+    plan.aur_order.push(name.to_string());
+
+    Ok(())
+}
+
+/// Directory AUR package git clones are cached in.
+pub fn aur_cache_dir() -> PathBuf {
+    crate::default_data_dir().join("aur_cache")
+}
+
+/// Clone (or reuse an already-cloned) `package`'s AUR repo.
+pub fn clone_package(package: &str) -> Result<PathBuf> {
+    let cache_dir = aur_cache_dir();
+    std::fs::create_dir_all(&cache_dir).map_err(|e| {
+        S1bCr4ftError::package(format!("Failed to create AUR cache directory: {}", e))
+    })?;
+
+    let repo_dir = cache_dir.join(package);
+    if repo_dir.exists() {
+        return Ok(repo_dir);
+    }
+
+    let url = format!("https://aur.archlinux.org/{}.git", package);
+    let status = std::process::Command::new("git")
+        .arg("clone")
+        .arg(&url)
+        .arg(&repo_dir)
+        .status()
+        .map_err(|e| S1bCr4ftError::package(format!("Failed to run git clone: {}", e)))?;
+
+    if !status.success() {
+        return Err(S1bCr4ftError::package(format!(
+            "Failed to clone AUR package '{}'",
+            package
+        )));
+    }
+
+    Ok(repo_dir)
+}
+
+/// Run `makepkg -si --noconfirm` in `dir`, building the package and
+/// installing it (and any repo dependencies makepkg resolves itself).
+pub fn build_package(dir: &Path) -> Result<()> {
+    let status = std::process::Command::new("makepkg")
+        .args(["-si", "--noconfirm"])
+        .current_dir(dir)
+        .status()
+        .map_err(|e| S1bCr4ftError::package(format!("Failed to run makepkg: {}", e)))?;
+
+    if !status.success() {
+        return Err(S1bCr4ftError::package(format!(
+            "makepkg failed in {}",
+            dir.display()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, depends: &[&str]) -> AurPackageInfo {
+        AurPackageInfo {
+            name: name.to_string(),
+            depends: depends.iter().map(|d| d.to_string()).collect(),
+            make_depends: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_strip_version_constraints() {
+        let stripped = strip_version_constraints(vec!["glibc>=2.38".to_string(), "curl".to_string()]);
+        assert_eq!(stripped, vec!["glibc".to_string(), "curl".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_build_order_separates_repo_and_aur_deps() {
+        let catalog: HashMap<&str, AurPackageInfo> = HashMap::from([
+            ("yay-bin", info("yay-bin", &["go", "git"])),
+            ("go", info("go", &[])),
+        ]);
+
+        let fetch = |name: &str| -> Result<AurPackageInfo> {
+            catalog
+                .get(name)
+                .cloned()
+                .ok_or_else(|| S1bCr4ftError::package(format!("unknown package: {}", name)))
+        };
+        let is_repo_package = |name: &str| matches!(name, "git");
+
+        let plan = resolve_build_order(
+            &["yay-bin".to_string()],
+            &fetch,
+            &is_repo_package,
+        )
+        .unwrap();
+
+        assert_eq!(plan.repo_deps, vec!["git".to_string()]);
+        assert_eq!(plan.aur_order, vec!["go".to_string(), "yay-bin".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_build_order_detects_cycle() {
+        let catalog: HashMap<&str, AurPackageInfo> = HashMap::from([
+            ("a", info("a", &["b"])),
+            ("b", info("b", &["a"])),
+        ]);
+
+        let fetch = |name: &str| -> Result<AurPackageInfo> {
+            catalog
+                .get(name)
+                .cloned()
+                .ok_or_else(|| S1bCr4ftError::package(format!("unknown package: {}", name)))
+        };
+        let is_repo_package = |_: &str| false;
+
+        let result = resolve_build_order(&["a".to_string()], &fetch, &is_repo_package);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! SQLite-backed cache of installed packages.
+//!
+//! `PackageManager::is_installed` used to spawn a `pacman -Q` process on
+//! every single lookup, which is slow when checking dozens of modules'
+//! package lists. `PackageCache` instead parses `pacman -Qi` once into a
+//! small SQLite table, so repeated lookups are just local queries. Call
+//! [`PackageCache::refresh`] after a sync installs or removes packages to
+//! keep it consistent with reality.
+
+use crate::error::{Result, S1bCr4ftError};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+
+struct CachedPackage {
+    name: String,
+    version: String,
+    description: String,
+    depends: String,
+    make_depends: String,
+}
+
+pub struct PackageCache {
+    conn: Connection,
+}
+
+impl PackageCache {
+    /// Default cache location, alongside the data the `AuditLogger` uses.
+    pub fn default_path() -> PathBuf {
+        crate::default_data_dir().join("package_cache.sqlite3")
+    }
+
+    /// Open (creating if needed) the cache at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if let Some(parent) = path.as_ref().parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    S1bCr4ftError::package(format!(
+                        "Failed to create package cache directory: {}",
+                        e
+                    ))
+                })?;
+            }
+        }
+
+        let conn = Connection::open(path.as_ref())
+            .map_err(|e| S1bCr4ftError::package(format!("Failed to open package cache: {}", e)))?;
+
+        Self::create_schema(&conn)?;
+
+        Ok(Self { conn })
+    }
+
+    fn create_schema(conn: &Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS packages (
+                name TEXT PRIMARY KEY,
+                version TEXT NOT NULL,
+                description TEXT NOT NULL DEFAULT '',
+                depends TEXT NOT NULL DEFAULT '',
+                make_depends TEXT NOT NULL DEFAULT ''
+            )",
+        )
+        .map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to create package cache schema: {}", e))
+        })
+    }
+
+    /// Rebuild the cache from `pacman -Qi`'s current output, replacing
+    /// whatever rows were there before.
+    pub fn refresh(&mut self) -> Result<()> {
+        let output = std::process::Command::new("pacman")
+            .arg("-Qi")
+            .output()
+            .map_err(|e| S1bCr4ftError::package(format!("Failed to run pacman -Qi: {}", e)))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let entries = Self::parse_pacman_qi(&stdout);
+
+        let tx = self.conn.transaction().map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to start package cache transaction: {}", e))
+        })?;
+
+        tx.execute("DELETE FROM packages", [])
+            .map_err(|e| S1bCr4ftError::package(format!("Failed to clear package cache: {}", e)))?;
+
+        for entry in &entries {
+            tx.execute(
+                "INSERT INTO packages (name, version, description, depends, make_depends) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    entry.name,
+                    entry.version,
+                    entry.description,
+                    entry.depends,
+                    entry.make_depends
+                ],
+            )
+            .map_err(|e| {
+                S1bCr4ftError::package(format!("Failed to insert cached package: {}", e))
+            })?;
+        }
+
+        tx.commit().map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to commit package cache refresh: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Drop every cached row without repopulating. Callers that know a sync
+    /// just changed installed packages should follow this with
+    /// [`Self::refresh`]; on its own this just makes every lookup report
+    /// "not installed" until the next refresh.
+    pub fn invalidate(&mut self) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM packages", [])
+            .map_err(|e| {
+                S1bCr4ftError::package(format!("Failed to invalidate package cache: {}", e))
+            })?;
+        Ok(())
+    }
+
+    pub fn is_installed(&self, name: &str) -> Result<bool> {
+        Ok(self.installed_version(name)?.is_some())
+    }
+
+    pub fn installed_version(&self, name: &str) -> Result<Option<String>> {
+        self.conn
+            .query_row(
+                "SELECT version FROM packages WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| {
+                S1bCr4ftError::package(format!("Failed to query package cache: {}", e))
+            })
+    }
+
+    /// Of `names`, return the ones NOT present in the cache, i.e. the
+    /// subset the sync planner still needs to hand to the package helper.
+    pub fn filter_missing(&self, names: &[String]) -> Result<Vec<String>> {
+        let mut missing = Vec::new();
+        for name in names {
+            if !self.is_installed(name)? {
+                missing.push(name.clone());
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Parse `pacman -Qi`'s blank-line-separated stanza format.
+    fn parse_pacman_qi(output: &str) -> Vec<CachedPackage> {
+        let mut entries = Vec::new();
+
+        for block in output.split("\n\n") {
+            if block.trim().is_empty() {
+                continue;
+            }
+
+            let mut name = String::new();
+            let mut version = String::new();
+            let mut description = String::new();
+            let mut depends = String::new();
+            let mut make_depends = String::new();
+
+            for line in block.lines() {
+                let Some((key, value)) = line.split_once(':') else {
+                    continue;
+                };
+                let value = value.trim().to_string();
+
+                match key.trim() {
+                    "Name" => name = value,
+                    "Version" => version = value,
+                    "Description" => description = value,
+                    "Depends On" => depends = value,
+                    "Make Deps" => make_depends = value,
+                    _ => {}
+                }
+            }
+
+            if !name.is_empty() {
+                entries.push(CachedPackage {
+                    name,
+                    version,
+                    description,
+                    depends,
+                    make_depends,
+                });
+            }
+        }
+
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_qi_output() -> &'static str {
+        "Name            : vim\n\
+         Version         : 9.1.0-1\n\
+         Description     : Vi Improved, a highly configurable editor\n\
+         Depends On      : gpm  libacl.so=1-64\n\
+         \n\
+         Name            : git\n\
+         Version         : 2.45.0-1\n\
+         Description     : Fast distributed version control system\n\
+         Depends On      : curl  perl\n"
+    }
+
+    #[test]
+    fn test_parse_pacman_qi_splits_stanzas() {
+        let entries = PackageCache::parse_pacman_qi(sample_qi_output());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "vim");
+        assert_eq!(entries[0].version, "9.1.0-1");
+        assert_eq!(entries[1].name, "git");
+    }
+
+    #[test]
+    fn test_cache_lookup_after_manual_insert() {
+        let mut cache = PackageCache::open(":memory:").unwrap();
+        cache
+            .conn
+            .execute(
+                "INSERT INTO packages (name, version) VALUES (?1, ?2)",
+                params!["vim", "9.1.0-1"],
+            )
+            .unwrap();
+
+        assert!(cache.is_installed("vim").unwrap());
+        assert!(!cache.is_installed("git").unwrap());
+        assert_eq!(
+            cache.installed_version("vim").unwrap(),
+            Some("9.1.0-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_filter_missing_keeps_only_uncached_names() {
+        let mut cache = PackageCache::open(":memory:").unwrap();
+        cache
+            .conn
+            .execute(
+                "INSERT INTO packages (name, version) VALUES (?1, ?2)",
+                params!["vim", "9.1.0-1"],
+            )
+            .unwrap();
+
+        let missing = cache
+            .filter_missing(&["vim".to_string(), "git".to_string()])
+            .unwrap();
+        assert_eq!(missing, vec!["git".to_string()]);
+    }
+
+    #[test]
+    fn test_invalidate_clears_cache() {
+        let mut cache = PackageCache::open(":memory:").unwrap();
+        cache
+            .conn
+            .execute(
+                "INSERT INTO packages (name, version) VALUES (?1, ?2)",
+                params!["vim", "9.1.0-1"],
+            )
+            .unwrap();
+
+        cache.invalidate().unwrap();
+        assert!(!cache.is_installed("vim").unwrap());
+    }
+}
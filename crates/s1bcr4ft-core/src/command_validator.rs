@@ -1,6 +1,7 @@
 use crate::error::{Result, S1bCr4ftError};
-use std::path::Path;
-use std::process::Command;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 /// Parsed command with executable and arguments
 #[derive(Debug, Clone, PartialEq)]
@@ -9,14 +10,732 @@ pub struct ParsedCommand {
     pub arguments: Vec<String>,
 }
 
+/// Detect the running host's platform configuration for [`CommandValidator`]
+/// whitelist `cfg` predicates: `init` (`"systemd"` if `/run/systemd/system`
+/// exists) and `glibc` (present iff this binary was built against glibc,
+/// which is effectively always true on Arch Linux).
+pub fn detect_platform_config() -> HashMap<String, String> {
+    let mut config = HashMap::new();
+
+    if Path::new("/run/systemd/system").is_dir() {
+        config.insert("init".to_string(), "systemd".to_string());
+    }
+
+    if cfg!(target_env = "gnu") {
+        config.insert("glibc".to_string(), String::new());
+    }
+
+    config
+}
+
+/// A `setrlimit` cap applied to a sandboxed child.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxRlimit {
+    /// RLIMIT_AS: maximum address space size, in bytes
+    AddressSpace(u64),
+    /// RLIMIT_NPROC: maximum number of processes for the real uid
+    NumProcesses(u64),
+    /// RLIMIT_FSIZE: maximum file size a write may create, in bytes
+    FileSize(u64),
+    /// RLIMIT_NOFILE: maximum number of open file descriptors
+    NumFiles(u64),
+}
+
+/// What a sandboxed process receives when it attempts a syscall outside its
+/// seccomp allow-list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeccompViolationAction {
+    /// Deliver SIGSYS to the process (the minijail default)
+    #[default]
+    Trap,
+    /// Fail the syscall with EPERM instead of killing the process
+    ReturnError,
+}
+
+/// Process confinement applied to a validated command before it is spawned,
+/// modeled on minijail's layered controls: privilege drop, namespace
+/// isolation, filesystem containment, resource limits, and a seccomp-bpf
+/// syscall allow-list.
+///
+/// Every control is opt-in via the builder methods below; an empty
+/// `Sandbox` spawns the child unconfined, identical to `execute_safe`.
+/// Controls are only enforced on Linux; see [`CommandValidator::execute_sandboxed`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sandbox {
+    uid: Option<u32>,
+    gid: Option<u32>,
+    new_user_namespace: bool,
+    new_mount_namespace: bool,
+    new_pid_namespace: bool,
+    root_dir: Option<std::path::PathBuf>,
+    readonly_root_with_tmpfs_tmp: bool,
+    rlimits: Vec<SandboxRlimit>,
+    seccomp_allowlist: Vec<String>,
+    seccomp_violation_action: SeccompViolationAction,
+}
+
+impl Sandbox {
+    /// Create an unconfined sandbox; chain the builder methods below to add controls.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Drop to `uid`/`gid` and clear supplementary groups before exec.
+    pub fn drop_privileges(mut self, uid: u32, gid: u32) -> Self {
+        self.uid = Some(uid);
+        self.gid = Some(gid);
+        self
+    }
+
+    /// Enter a new user namespace.
+    pub fn new_user_namespace(mut self) -> Self {
+        self.new_user_namespace = true;
+        self
+    }
+
+    /// Enter a new mount namespace.
+    pub fn new_mount_namespace(mut self) -> Self {
+        self.new_mount_namespace = true;
+        self
+    }
+
+    /// Enter a new PID namespace.
+    pub fn new_pid_namespace(mut self) -> Self {
+        self.new_pid_namespace = true;
+        self
+    }
+
+    /// `chroot` into `dir` before exec. Requires [`Sandbox::new_mount_namespace`].
+    pub fn chroot<P: Into<std::path::PathBuf>>(mut self, dir: P) -> Self {
+        self.root_dir = Some(dir.into());
+        self
+    }
+
+    /// Remount `/` read-only and mount a tmpfs over `/tmp`, inside the new
+    /// mount namespace. Requires [`Sandbox::new_mount_namespace`].
+    pub fn readonly_root_with_tmpfs_tmp(mut self) -> Self {
+        self.readonly_root_with_tmpfs_tmp = true;
+        self
+    }
+
+    /// Add a `setrlimit` cap.
+    pub fn rlimit(mut self, limit: SandboxRlimit) -> Self {
+        self.rlimits.push(limit);
+        self
+    }
+
+    /// Install a seccomp-bpf filter that only allows the named syscalls
+    /// (e.g. `"read"`, `"write"`, `"mmap"`); anything else triggers
+    /// `on_violation`. Unknown syscall names are rejected when the sandbox
+    /// is applied.
+    pub fn seccomp_allow<I, S>(mut self, syscalls: I, on_violation: SeccompViolationAction) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.seccomp_allowlist = syscalls.into_iter().map(Into::into).collect();
+        self.seccomp_violation_action = on_violation;
+        self
+    }
+}
+
+/// Which stream a [`StreamEvent::Output`] chunk came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamSource {
+    Stdout,
+    Stderr,
+}
+
+/// One decoded unit of output from a streaming command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Chunk {
+    /// A run of bytes that decoded cleanly as UTF-8
+    Text(String),
+    /// Bytes that didn't decode as UTF-8, passed through unmodified
+    Binary(Vec<u8>),
+}
+
+/// A single item yielded by [`CommandStream`]: either a decoded chunk
+/// tagged with the stream it came from, or the child's exit status once it
+/// has run to completion (always the last item).
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    Output { source: StreamSource, chunk: Chunk },
+    Exited(std::process::ExitStatus),
+}
+
+/// Incrementally decodes a byte stream as UTF-8, tolerating chunk
+/// boundaries that split a multi-byte sequence.
+///
+/// Borrowed from how interactive shells decode PTY output: try
+/// `str::from_utf8` on the accumulated buffer; if the only problem is an
+/// incomplete sequence in the trailing few bytes (up to 4, the longest a
+/// UTF-8 sequence can be), hold those bytes back for the next read instead
+/// of reporting them as invalid. Any other decode failure means the bytes
+/// genuinely aren't UTF-8, so they're passed through as [`Chunk::Binary`].
+struct Utf8ChunkDecoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8ChunkDecoder {
+    fn new() -> Self {
+        Self { pending: Vec::new() }
+    }
+
+    fn decode(&mut self, bytes: &[u8]) -> Option<Chunk> {
+        self.pending.extend_from_slice(bytes);
+        if self.pending.is_empty() {
+            return None;
+        }
+
+        match std::str::from_utf8(&self.pending) {
+            Ok(text) => {
+                let chunk = Chunk::Text(text.to_string());
+                self.pending.clear();
+                Some(chunk)
+            }
+            Err(e) => {
+                let valid_up_to = e.valid_up_to();
+                let trailing = self.pending.len() - valid_up_to;
+
+                if e.error_len().is_none() && trailing <= 4 {
+                    if valid_up_to == 0 {
+                        // Not even a valid prefix yet; wait for more bytes.
+                        return None;
+                    }
+                    let text = String::from_utf8(self.pending[..valid_up_to].to_vec())
+                        .expect("valid_up_to bounds a known-valid UTF-8 prefix");
+                    self.pending.drain(..valid_up_to);
+                    Some(Chunk::Text(text))
+                } else {
+                    Some(Chunk::Binary(std::mem::take(&mut self.pending)))
+                }
+            }
+        }
+    }
+
+    /// Flush whatever is left at EOF. An incomplete sequence that never
+    /// completed is genuinely invalid, so it goes out as `Binary`.
+    fn flush(&mut self) -> Option<Chunk> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(Chunk::Binary(std::mem::take(&mut self.pending)))
+        }
+    }
+}
+
+fn read_stream<R: std::io::Read>(
+    mut reader: R,
+    source: StreamSource,
+    sender: std::sync::mpsc::Sender<StreamEvent>,
+) {
+    let mut decoder = Utf8ChunkDecoder::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                if let Some(chunk) = decoder.decode(&buf[..n]) {
+                    if sender.send(StreamEvent::Output { source, chunk }).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(_) => break,
+        }
+    }
+    if let Some(chunk) = decoder.flush() {
+        let _ = sender.send(StreamEvent::Output { source, chunk });
+    }
+}
+
+/// Live output from a command spawned via
+/// [`CommandValidator::execute_streaming`]. Yields each decoded chunk as it
+/// arrives, tagged with its source stream, followed by a final
+/// `StreamEvent::Exited` once the child has run to completion.
+pub struct CommandStream {
+    receiver: std::sync::mpsc::Receiver<StreamEvent>,
+}
+
+impl Iterator for CommandStream {
+    type Item = StreamEvent;
+
+    fn next(&mut self) -> Option<StreamEvent> {
+        self.receiver.recv().ok()
+    }
+}
+
+/// A POSIX signal, identified by number or name, for use with
+/// [`CommandValidator::reset_signals`]/[`CommandValidator::ignore_signals`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signal(libc::c_int);
+
+impl Signal {
+    /// Build a signal from its raw number directly (e.g. `15` for `SIGTERM`).
+    pub fn from_number(number: i32) -> Self {
+        Signal(number)
+    }
+
+    /// Parse a signal by number (`"15"`), bare name (`"TERM"`), or
+    /// `SIG`-prefixed name (`"SIGTERM"`).
+    pub fn parse(name: &str) -> Result<Self> {
+        if let Ok(number) = name.parse::<i32>() {
+            return Ok(Signal(number));
+        }
+
+        let stripped = name.strip_prefix("SIG").unwrap_or(name);
+        let number = match stripped {
+            "HUP" => libc::SIGHUP,
+            "INT" => libc::SIGINT,
+            "QUIT" => libc::SIGQUIT,
+            "ILL" => libc::SIGILL,
+            "TRAP" => libc::SIGTRAP,
+            "ABRT" => libc::SIGABRT,
+            "BUS" => libc::SIGBUS,
+            "FPE" => libc::SIGFPE,
+            "KILL" => libc::SIGKILL,
+            "USR1" => libc::SIGUSR1,
+            "SEGV" => libc::SIGSEGV,
+            "USR2" => libc::SIGUSR2,
+            "PIPE" => libc::SIGPIPE,
+            "ALRM" => libc::SIGALRM,
+            "TERM" => libc::SIGTERM,
+            "CHLD" => libc::SIGCHLD,
+            "CONT" => libc::SIGCONT,
+            "STOP" => libc::SIGSTOP,
+            "TSTP" => libc::SIGTSTP,
+            "TTIN" => libc::SIGTTIN,
+            "TTOU" => libc::SIGTTOU,
+            "URG" => libc::SIGURG,
+            "XCPU" => libc::SIGXCPU,
+            "XFSZ" => libc::SIGXFSZ,
+            "VTALRM" => libc::SIGVTALRM,
+            "PROF" => libc::SIGPROF,
+            "WINCH" => libc::SIGWINCH,
+            "IO" => libc::SIGIO,
+            "SYS" => libc::SIGSYS,
+            _ => {
+                return Err(S1bCr4ftError::package(format!(
+                    "Unknown signal name: {}",
+                    name
+                )))
+            }
+        };
+        Ok(Signal(number))
+    }
+
+    fn number(self) -> libc::c_int {
+        self.0
+    }
+
+    /// `SIGKILL`/`SIGSTOP` dispositions can't be changed by the process
+    /// itself; callers skip them rather than fail.
+    fn is_fixed(self) -> bool {
+        self.0 == libc::SIGKILL || self.0 == libc::SIGSTOP
+    }
+}
+
+/// Reset or ignore the given signals' disposition in the child, before
+/// `execve`. `SIGKILL`/`SIGSTOP` are silently skipped since their
+/// disposition can't be changed.
+#[cfg(unix)]
+fn apply_signal_dispositions(reset: &[Signal], ignore: &[Signal]) -> std::io::Result<()> {
+    for signal in reset {
+        if signal.is_fixed() {
+            continue;
+        }
+        if unsafe { libc::signal(signal.number(), libc::SIG_DFL) } == libc::SIG_ERR {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    for signal in ignore {
+        if signal.is_fixed() {
+            continue;
+        }
+        if unsafe { libc::signal(signal.number(), libc::SIG_IGN) } == libc::SIG_ERR {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// How the spawned child's environment is derived from the parent's,
+/// mirroring the `env` tool's `-i`/`--ignore-environment` modes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EnvMode {
+    /// Inherit the parent's environment unmodified
+    Inherit,
+    /// Start from an empty environment
+    Clear,
+    /// Start from empty, then pass through only the named variables
+    AllowList(Vec<String>),
+}
+
+impl Default for EnvMode {
+    fn default() -> Self {
+        EnvMode::Inherit
+    }
+}
+
+/// Validate an environment variable name: alphanumeric plus `_`, not
+/// starting with a digit.
+fn validate_env_key(key: &str) -> Result<()> {
+    let mut chars = key.chars();
+    let starts_safely = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_');
+    if !starts_safely || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(S1bCr4ftError::package(format!(
+            "Invalid environment variable name: {}",
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// Conservative default byte budget for a single invocation's argv, well
+/// under Linux's actual `ARG_MAX` (typically 2MiB), to leave headroom for
+/// environment size varying across systems.
+pub const DEFAULT_ARG_BYTE_BUDGET: usize = 131_072;
+
+/// Options for [`CommandValidator::execute_batch`].
+#[derive(Debug, Clone)]
+pub struct BatchOptions {
+    /// Maximum bytes per invocation's argv, accounting for the executable,
+    /// fixed args, per-arg NUL overhead, and an estimate of environment
+    /// size. Defaults to [`DEFAULT_ARG_BYTE_BUDGET`].
+    pub byte_budget: usize,
+    /// Cap on how many items can be folded into a single invocation,
+    /// regardless of byte budget.
+    pub max_args_per_call: Option<usize>,
+    /// If set, each item replaces every occurrence of this placeholder
+    /// (e.g. `"{}"`, for `-I{}`-style templates) in the base argv's
+    /// arguments, instead of being appended as a fresh trailing argument.
+    /// Implies exactly one item per invocation.
+    pub replace_placeholder: Option<String>,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            byte_budget: DEFAULT_ARG_BYTE_BUDGET,
+            max_args_per_call: None,
+            replace_placeholder: None,
+        }
+    }
+}
+
+fn argv_byte_size(executable: &str, arguments: &[String]) -> usize {
+    let mut size = executable.len() + 1; // + NUL terminator
+    for arg in arguments {
+        size += arg.len() + 1;
+    }
+    size
+}
+
+fn build_batch_command(base: &ParsedCommand, batch: &[String], placeholder: Option<&str>) -> ParsedCommand {
+    match placeholder {
+        Some(marker) => {
+            // replace_placeholder implies exactly one item per call.
+            let item = &batch[0];
+            let arguments = base
+                .arguments
+                .iter()
+                .map(|arg| arg.replace(marker, item))
+                .collect();
+            ParsedCommand {
+                executable: base.executable.clone(),
+                arguments,
+            }
+        }
+        None => {
+            let mut arguments = base.arguments.clone();
+            arguments.extend(batch.iter().cloned());
+            ParsedCommand {
+                executable: base.executable.clone(),
+                arguments,
+            }
+        }
+    }
+}
+
+/// A `cfg(...)`-style predicate gating whether a whitelisted executable is
+/// allowed under the active platform configuration.
+///
+/// Evaluated against a caller-supplied key/value set (e.g. `target_os`,
+/// `init = "systemd"`, `arch`) via [`CfgExpr::eval`]. Grammar:
+///
+/// ```text
+/// cfg-expr := ident | ident "=" string
+///           | "all" "(" cfg-expr ("," cfg-expr)* ")"
+///           | "any" "(" cfg-expr ("," cfg-expr)* ")"
+///           | "not" "(" cfg-expr ")"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgExpr {
+    /// True if `ident` is present in the key set, regardless of value.
+    Ident(String),
+    /// True if the exact `key = "value"` pair is present.
+    KeyValue(String, String),
+    /// Logical AND over its children.
+    All(Vec<CfgExpr>),
+    /// Logical OR over its children.
+    Any(Vec<CfgExpr>),
+    /// Negation of its child.
+    Not(Box<CfgExpr>),
+}
+
+impl CfgExpr {
+    /// Parse a `cfg-expr` from its textual grammar.
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut parser = CfgExprParser::new(input);
+        let expr = parser.parse_expr()?;
+        parser.expect_end()?;
+        Ok(expr)
+    }
+
+    /// Evaluate this predicate against a key/value configuration.
+    pub fn eval(&self, config: &HashMap<String, String>) -> bool {
+        match self {
+            CfgExpr::Ident(key) => config.contains_key(key),
+            CfgExpr::KeyValue(key, value) => config.get(key).map(|v| v == value).unwrap_or(false),
+            CfgExpr::All(children) => children.iter().all(|c| c.eval(config)),
+            CfgExpr::Any(children) => children.iter().any(|c| c.eval(config)),
+            CfgExpr::Not(child) => !child.eval(config),
+        }
+    }
+}
+
+impl std::fmt::Display for CfgExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfgExpr::Ident(key) => write!(f, "{}", key),
+            CfgExpr::KeyValue(key, value) => write!(f, "{} = \"{}\"", key, value),
+            CfgExpr::All(children) => {
+                write!(f, "all(")?;
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", child)?;
+                }
+                write!(f, ")")
+            }
+            CfgExpr::Any(children) => {
+                write!(f, "any(")?;
+                for (i, child) in children.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", child)?;
+                }
+                write!(f, ")")
+            }
+            CfgExpr::Not(child) => write!(f, "not({})", child),
+        }
+    }
+}
+
+/// Hand-rolled recursive-descent parser for [`CfgExpr`], mirroring the
+/// index-based style of the argument tokenizer ([`tokenize`]) below.
+struct CfgExprParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl CfgExprParser {
+    fn new(input: &str) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(c) if c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(S1bCr4ftError::package(format!(
+                "expected '{}' but found '{}' at position {} in cfg expression",
+                expected, c, self.pos
+            ))),
+            None => Err(S1bCr4ftError::package(format!(
+                "expected '{}' but found end of input in cfg expression",
+                expected
+            ))),
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(()),
+            Some(c) => Err(S1bCr4ftError::package(format!(
+                "unexpected trailing '{}' at position {} in cfg expression",
+                c, self.pos
+            ))),
+        }
+    }
+
+    fn parse_ident(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_alphanumeric() || c == '_' || c == '-') {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(S1bCr4ftError::package(format!(
+                "expected identifier at position {} in cfg expression",
+                self.pos
+            )));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c != '"') {
+            self.pos += 1;
+        }
+        if self.peek().is_none() {
+            return Err(S1bCr4ftError::package(
+                "unterminated string literal in cfg expression".to_string(),
+            ));
+        }
+        let value = self.chars[start..self.pos].iter().collect();
+        self.pos += 1; // closing quote
+        Ok(value)
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<CfgExpr>> {
+        self.expect('(')?;
+        let mut items = vec![self.parse_expr()?];
+        self.skip_whitespace();
+        while self.peek() == Some(',') {
+            self.pos += 1;
+            items.push(self.parse_expr()?);
+            self.skip_whitespace();
+        }
+        self.expect(')')?;
+        Ok(items)
+    }
+
+    fn parse_expr(&mut self) -> Result<CfgExpr> {
+        self.skip_whitespace();
+        let ident = self.parse_ident()?;
+
+        match ident.as_str() {
+            "all" => Ok(CfgExpr::All(self.parse_list()?)),
+            "any" => Ok(CfgExpr::Any(self.parse_list()?)),
+            "not" => {
+                self.expect('(')?;
+                let inner = self.parse_expr()?;
+                self.expect(')')?;
+                Ok(CfgExpr::Not(Box::new(inner)))
+            }
+            _ => {
+                self.skip_whitespace();
+                if self.peek() == Some('=') {
+                    self.pos += 1;
+                    let value = self.parse_string()?;
+                    Ok(CfgExpr::KeyValue(ident, value))
+                } else {
+                    Ok(CfgExpr::Ident(ident))
+                }
+            }
+        }
+    }
+}
+
+/// An entry in a [`CommandValidator`]'s whitelist: an allowed executable,
+/// optionally gated by a [`CfgExpr`] evaluated against the validator's
+/// platform configuration.
+#[derive(Debug, Clone)]
+pub struct WhitelistEntry {
+    pub executable: String,
+    pub cfg: Option<CfgExpr>,
+}
+
+impl From<String> for WhitelistEntry {
+    fn from(executable: String) -> Self {
+        Self {
+            executable,
+            cfg: None,
+        }
+    }
+}
+
+impl From<&str> for WhitelistEntry {
+    fn from(executable: &str) -> Self {
+        Self {
+            executable: executable.to_string(),
+            cfg: None,
+        }
+    }
+}
+
+impl From<(String, CfgExpr)> for WhitelistEntry {
+    fn from((executable, cfg): (String, CfgExpr)) -> Self {
+        Self {
+            executable,
+            cfg: Some(cfg),
+        }
+    }
+}
+
+impl From<(String, Option<CfgExpr>)> for WhitelistEntry {
+    fn from((executable, cfg): (String, Option<CfgExpr>)) -> Self {
+        Self { executable, cfg }
+    }
+}
+
+impl From<(&str, CfgExpr)> for WhitelistEntry {
+    fn from((executable, cfg): (&str, CfgExpr)) -> Self {
+        Self {
+            executable: executable.to_string(),
+            cfg: Some(cfg),
+        }
+    }
+}
+
 /// Command validator with whitelist and sanitization
 pub struct CommandValidator {
-    /// Whitelist of allowed executables
-    allowed_executables: Vec<String>,
+    /// Whitelist of allowed executables, each optionally gated by a
+    /// [`CfgExpr`] evaluated against `platform_config`
+    allowed_executables: Vec<WhitelistEntry>,
     /// Whether to allow absolute paths
     allow_absolute_paths: bool,
     /// Whether to allow shell metacharacters in arguments
     allow_shell_metachars: bool,
+    /// Signals to reset to SIG_DFL in the child before execve
+    reset_signals: Vec<Signal>,
+    /// Signals to set to SIG_IGN in the child before execve
+    ignore_signals: Vec<Signal>,
+    /// How the child's environment is derived from the parent's
+    env_mode: EnvMode,
+    /// Variables set in the child's environment, applied after `env_mode`
+    env_overrides: HashMap<String, String>,
+    /// Variables removed from the child's environment
+    env_removals: Vec<String>,
+    /// If set, resolve executables against these directories instead of
+    /// the inherited `PATH`
+    trusted_path: Option<Vec<PathBuf>>,
+    /// Key/value configuration (e.g. `target_os`, `init`, `arch`) that
+    /// whitelist entries' [`CfgExpr`] predicates are evaluated against
+    platform_config: HashMap<String, String>,
 }
 
 impl Default for CommandValidator {
@@ -34,39 +753,58 @@ impl CommandValidator {
     /// - groupmod, groupadd, groupdel (group management)
     /// - sysctl (kernel parameter configuration)
     /// - udevadm (device management)
-    /// - locale-gen (locale generation)
+    /// - locale-gen (locale generation, glibc only)
     /// - hwclock (hardware clock)
-    /// - timedatectl (time management)
+    /// - timedatectl (time management, systemd only)
     pub fn new() -> Self {
-        Self {
-            allowed_executables: vec![
-                "systemctl".to_string(),
-                "usermod".to_string(),
-                "useradd".to_string(),
-                "userdel".to_string(),
-                "groupmod".to_string(),
-                "groupadd".to_string(),
-                "groupdel".to_string(),
-                "sysctl".to_string(),
-                "udevadm".to_string(),
-                "locale-gen".to_string(),
-                "hwclock".to_string(),
-                "timedatectl".to_string(),
-            ],
-            allow_absolute_paths: false,
-            allow_shell_metachars: false,
-        }
+        Self::with_whitelist(vec![
+            WhitelistEntry::from("systemctl"),
+            WhitelistEntry::from("usermod"),
+            WhitelistEntry::from("useradd"),
+            WhitelistEntry::from("userdel"),
+            WhitelistEntry::from("groupmod"),
+            WhitelistEntry::from("groupadd"),
+            WhitelistEntry::from("groupdel"),
+            WhitelistEntry::from("sysctl"),
+            WhitelistEntry::from("udevadm"),
+            ("locale-gen", CfgExpr::Ident("glibc".to_string())).into(),
+            WhitelistEntry::from("hwclock"),
+            ("timedatectl", CfgExpr::KeyValue("init".to_string(), "systemd".to_string())).into(),
+        ])
     }
 
     /// Create a custom validator with explicit whitelist
-    pub fn with_whitelist(allowed: Vec<String>) -> Self {
+    ///
+    /// Accepts anything convertible into a [`WhitelistEntry`]: bare
+    /// executable names (`&str`/`String`) with no `cfg` gate, or
+    /// `(name, CfgExpr)`/`(name, Option<CfgExpr>)` pairs.
+    pub fn with_whitelist<I, E>(allowed: I) -> Self
+    where
+        I: IntoIterator<Item = E>,
+        E: Into<WhitelistEntry>,
+    {
         Self {
-            allowed_executables: allowed,
+            allowed_executables: allowed.into_iter().map(Into::into).collect(),
             allow_absolute_paths: false,
             allow_shell_metachars: false,
+            reset_signals: Vec::new(),
+            ignore_signals: Vec::new(),
+            env_mode: EnvMode::default(),
+            env_overrides: HashMap::new(),
+            env_removals: Vec::new(),
+            trusted_path: None,
+            platform_config: HashMap::new(),
         }
     }
 
+    /// Set the platform configuration that whitelist entries' [`CfgExpr`]
+    /// predicates are evaluated against (e.g. `target_os=linux`,
+    /// `init=systemd`)
+    pub fn with_platform_config(mut self, config: HashMap<String, String>) -> Self {
+        self.platform_config = config;
+        self
+    }
+
     /// Allow absolute paths in executables
     pub fn allow_absolute_paths(mut self) -> Self {
         self.allow_absolute_paths = true;
@@ -79,12 +817,127 @@ impl CommandValidator {
         self
     }
 
+    /// Reset the given signals' disposition to `SIG_DFL` in spawned
+    /// children, before `execve`
+    ///
+    /// Without this, a child inherits the parent's signal dispositions,
+    /// which is wrong when spawning supervised/daemon processes like
+    /// `systemctl`. Matches `env --ignore-signal`'s complement.
+    pub fn reset_signals(mut self, signals: &[Signal]) -> Self {
+        self.reset_signals = signals.to_vec();
+        self
+    }
+
+    /// Set the given signals' disposition to `SIG_IGN` in spawned children,
+    /// before `execve`. Matches `env --ignore-signal` semantics.
+    pub fn ignore_signals(mut self, signals: &[Signal]) -> Self {
+        self.ignore_signals = signals.to_vec();
+        self
+    }
+
+    /// Start the spawned child's environment from empty, dropping
+    /// everything inherited from the parent
+    ///
+    /// Without this, the full parent environment (including secrets or an
+    /// attacker-controlled `PATH`/`LD_*`) is passed straight through to the
+    /// child, which argv sanitization alone cannot prevent.
+    pub fn clear_env(mut self) -> Self {
+        self.env_mode = EnvMode::Clear;
+        self
+    }
+
+    /// Start the spawned child's environment from empty, then pass through
+    /// only the named variables from the parent's environment
+    pub fn allow_env(mut self, keys: &[&str]) -> Self {
+        self.env_mode = EnvMode::AllowList(keys.iter().map(|s| s.to_string()).collect());
+        self
+    }
+
+    /// Set an environment variable in the spawned child, overriding
+    /// whatever `clear_env`/`allow_env` would otherwise produce
+    pub fn set_env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Result<Self> {
+        let key = key.into();
+        validate_env_key(&key)?;
+        self.env_overrides.insert(key, value.into());
+        Ok(self)
+    }
+
+    /// Remove an environment variable from the spawned child
+    pub fn unset_env<K: Into<String>>(mut self, key: K) -> Result<Self> {
+        let key = key.into();
+        validate_env_key(&key)?;
+        self.env_removals.push(key);
+        Ok(self)
+    }
+
+    /// Resolve executables against `dirs` instead of the inherited `PATH`
+    ///
+    /// Since the default whitelist forbids absolute paths, this is the
+    /// supported way to point at trusted binaries that live outside the
+    /// system `PATH` without trusting the caller's environment to find them.
+    pub fn trusted_path(mut self, dirs: Vec<PathBuf>) -> Self {
+        self.trusted_path = Some(dirs);
+        self
+    }
+
+    /// Resolve `executable` against `trusted_path`, if configured;
+    /// otherwise leave it as-is for `Command`/`PATH` lookup to resolve.
+    fn resolve_executable(&self, executable: &str) -> Result<PathBuf> {
+        match &self.trusted_path {
+            None => Ok(PathBuf::from(executable)),
+            Some(dirs) => dirs
+                .iter()
+                .map(|dir| dir.join(executable))
+                .find(|candidate| candidate.is_file())
+                .ok_or_else(|| {
+                    S1bCr4ftError::package(format!(
+                        "Executable '{}' not found in trusted PATH: {:?}",
+                        executable, dirs
+                    ))
+                }),
+        }
+    }
+
+    /// Apply `env_mode`/`env_overrides`/`env_removals` to a [`Command`]
+    /// before it's spawned.
+    fn apply_env_to(&self, cmd: &mut Command) {
+        match &self.env_mode {
+            EnvMode::Inherit => {}
+            EnvMode::Clear => {
+                cmd.env_clear();
+            }
+            EnvMode::AllowList(keys) => {
+                cmd.env_clear();
+                for key in keys {
+                    if let Ok(value) = std::env::var(key) {
+                        cmd.env(key, value);
+                    }
+                }
+            }
+        }
+        for key in &self.env_removals {
+            cmd.env_remove(key);
+        }
+        for (key, value) in &self.env_overrides {
+            cmd.env(key, value);
+        }
+    }
+
     /// Parse a command string into executable and arguments
     ///
-    /// This uses a simple parser that respects quotes and escaping:
-    /// - Single quotes: 'text'
-    /// - Double quotes: "text"
-    /// - Backslashes: \escape
+    /// A small explicit-state POSIX-style word splitter, not a shell: it
+    /// understands quoting and escaping but never performs globbing,
+    /// variable expansion, or command substitution.
+    ///
+    /// - Quoted and unquoted runs within the same whitespace-delimited word
+    ///   concatenate into a single argument (`--opt="a b"` -> `--opt=a b`).
+    /// - Backslash is literal inside single quotes, an escape for `"`,
+    ///   `\`, `` ` ``, and `$` inside double quotes, and a general escape
+    ///   outside quotes.
+    /// - ANSI-C `$'...'` quoting supports `\n`, `\t`, `\r`, `\\`, `\'`,
+    ///   `\xHH`, and `\0NNN` escapes.
+    /// - An unterminated `'`, `"`, or `$'` at end of input is an error,
+    ///   rather than silently accepted.
     ///
     /// # Examples
     ///
@@ -102,45 +955,7 @@ impl CommandValidator {
             return Err(S1bCr4ftError::package("Empty command".to_string()));
         }
 
-        // Simple shell-like parser
-        let chars = trimmed.chars().peekable();
-        let mut parts = Vec::new();
-        let mut current = String::new();
-        let mut in_single_quote = false;
-        let mut in_double_quote = false;
-        let mut escaped = false;
-
-        for c in chars {
-            match c {
-                '\\' if !in_single_quote && !escaped => {
-                    escaped = true;
-                }
-                '\'' if !in_double_quote && !escaped => {
-                    in_single_quote = !in_single_quote;
-                }
-                '"' if !in_single_quote && !escaped => {
-                    in_double_quote = !in_double_quote;
-                }
-                ' ' | '\t' if !in_single_quote && !in_double_quote && !escaped => {
-                    if !current.is_empty() {
-                        parts.push(current.clone());
-                        current.clear();
-                    }
-                }
-                _ => {
-                    if !escaped {
-                        current.push(c);
-                    } else {
-                        current.push(c);
-                        escaped = false;
-                    }
-                }
-            }
-        }
-
-        if !current.is_empty() {
-            parts.push(current);
-        }
+        let parts = tokenize(trimmed)?;
 
         if parts.is_empty() {
             return Err(S1bCr4ftError::package(
@@ -217,11 +1032,27 @@ impl CommandValidator {
         }
 
         // Check whitelist
-        if !self.allowed_executables.contains(&executable.to_string()) {
-            return Err(S1bCr4ftError::package(format!(
-                "Executable not in whitelist: {}. Allowed: {:?}",
-                executable, self.allowed_executables
-            )));
+        let entry = self
+            .allowed_executables
+            .iter()
+            .find(|e| e.executable == executable);
+        match entry {
+            None => {
+                return Err(S1bCr4ftError::package(format!(
+                    "Executable not in whitelist: {}. Allowed: {:?}",
+                    executable, self.allowed_executables
+                )));
+            }
+            Some(entry) => {
+                if let Some(cfg) = &entry.cfg {
+                    if !cfg.eval(&self.platform_config) {
+                        return Err(S1bCr4ftError::package(format!(
+                            "Executable '{}' not allowed under current platform config: unsatisfied predicate `{}`",
+                            executable, cfg
+                        )));
+                    }
+                }
+            }
         }
 
         // Validate executable name characters
@@ -290,21 +1121,768 @@ impl CommandValidator {
     /// - No shell is involved
     /// - Arguments are passed directly to the process
     pub fn execute_safe(&self, command: &ParsedCommand) -> Result<std::process::Output> {
-        Command::new(&command.executable)
-            .args(&command.arguments)
-            .output()
-            .map_err(|e| {
-                S1bCr4ftError::package(format!("Failed to execute {}: {}", command.executable, e))
+        let resolved = self.resolve_executable(&command.executable)?;
+        let mut cmd = Command::new(&resolved);
+        cmd.args(&command.arguments);
+        self.apply_signal_dispositions_to(&mut cmd);
+        self.apply_env_to(&mut cmd);
+
+        cmd.output().map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to execute {}: {}", command.executable, e))
+        })
+    }
+
+    /// Register a `pre_exec` hook applying [`CommandValidator::reset_signals`]
+    /// and [`CommandValidator::ignore_signals`], if either was configured.
+    #[cfg(unix)]
+    fn apply_signal_dispositions_to(&self, cmd: &mut Command) {
+        use std::os::unix::process::CommandExt;
+
+        if self.reset_signals.is_empty() && self.ignore_signals.is_empty() {
+            return;
+        }
+        let reset = self.reset_signals.clone();
+        let ignore = self.ignore_signals.clone();
+        // SAFETY: `apply_signal_dispositions` only calls the async-signal-safe
+        // `signal(2)`, as required between `fork()` and `exec()`.
+        unsafe {
+            cmd.pre_exec(move || apply_signal_dispositions(&reset, &ignore));
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn apply_signal_dispositions_to(&self, _cmd: &mut Command) {}
+
+    /// Execute a validated command confined by a [`Sandbox`]
+    ///
+    /// Applies every control configured on `sandbox` via a `pre_exec`
+    /// closure before the child execs: privilege drop, namespace entry,
+    /// filesystem containment, `setrlimit` caps, and a seccomp-bpf syscall
+    /// allow-list. Whitelisting the executable stops injection; this stops
+    /// the whitelisted process from doing anything unexpected once it runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error before spawning if `sandbox` requests a control
+    /// that's unsupported on this platform or an unknown syscall name, and
+    /// an error after spawning if the child reports a `pre_exec` failure
+    /// (e.g. a namespace/seccomp operation the current kernel doesn't
+    /// support).
+    ///
+    /// # Security
+    ///
+    /// This should only be called after `parse_and_validate()`, same as
+    /// [`CommandValidator::execute_safe`].
+    #[cfg(target_os = "linux")]
+    pub fn execute_sandboxed(
+        &self,
+        command: &ParsedCommand,
+        sandbox: &Sandbox,
+    ) -> Result<std::process::Output> {
+        use std::os::unix::process::CommandExt;
+
+        sandbox.validate_supported()?;
+        let prepared = sandbox
+            .prepare()
+            .map_err(|e| S1bCr4ftError::package(format!("Failed to prepare sandbox: {}", e)))?;
+
+        let resolved = self.resolve_executable(&command.executable)?;
+        let sandbox = sandbox.clone();
+        let mut cmd = Command::new(&resolved);
+        cmd.args(&command.arguments);
+        self.apply_signal_dispositions_to(&mut cmd);
+        self.apply_env_to(&mut cmd);
+
+        // SAFETY: `Sandbox::apply` only calls async-signal-safe libc
+        // functions (no allocation, no locking), as required between
+        // `fork()` and `exec()` - every `CString` it touches was already
+        // allocated above, in `prepared`.
+        unsafe {
+            cmd.pre_exec(move || sandbox.apply(&prepared));
+        }
+
+        cmd.output().map_err(|e| {
+            S1bCr4ftError::package(format!(
+                "Failed to execute sandboxed {}: {}",
+                command.executable, e
+            ))
+        })
+    }
+
+    /// Execute a validated command confined by a [`Sandbox`]
+    ///
+    /// Sandboxing relies on Linux namespaces, `setrlimit`, and seccomp-bpf,
+    /// none of which exist on this platform.
+    #[cfg(not(target_os = "linux"))]
+    pub fn execute_sandboxed(
+        &self,
+        _command: &ParsedCommand,
+        _sandbox: &Sandbox,
+    ) -> Result<std::process::Output> {
+        Err(S1bCr4ftError::package(
+            "Process sandboxing is only supported on Linux".to_string(),
+        ))
+    }
+
+    /// Execute a validated command with piped stdout/stderr, streaming
+    /// output as it arrives instead of buffering until the child exits
+    ///
+    /// Unlike [`CommandValidator::execute_safe`], this never accumulates the
+    /// full output in memory, so it's the right choice for long-running
+    /// commands (`systemctl`, `udevadm`) or ones that emit megabytes. Each
+    /// item from the returned [`CommandStream`] carries which stream it came
+    /// from and whether it decoded as UTF-8 [`Chunk::Text`] or fell back to
+    /// [`Chunk::Binary`]; the final item is the child's `ExitStatus`.
+    ///
+    /// # Security
+    ///
+    /// This should only be called after `parse_and_validate()`, same as
+    /// [`CommandValidator::execute_safe`].
+    pub fn execute_streaming(&self, command: &ParsedCommand) -> Result<CommandStream> {
+        let resolved = self.resolve_executable(&command.executable)?;
+        let mut cmd = Command::new(&resolved);
+        cmd.args(&command.arguments)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        self.apply_signal_dispositions_to(&mut cmd);
+        self.apply_env_to(&mut cmd);
+
+        let mut child = cmd.spawn().map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to spawn {}: {}", command.executable, e))
+        })?;
+
+        let stdout = child.stdout.take().expect("stdout was piped");
+        let stderr = child.stderr.take().expect("stderr was piped");
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let stdout_sender = sender.clone();
+        let stdout_thread =
+            std::thread::spawn(move || read_stream(stdout, StreamSource::Stdout, stdout_sender));
+
+        let stderr_sender = sender.clone();
+        let stderr_thread =
+            std::thread::spawn(move || read_stream(stderr, StreamSource::Stderr, stderr_sender));
+
+        std::thread::spawn(move || {
+            let _ = stdout_thread.join();
+            let _ = stderr_thread.join();
+            if let Ok(status) = child.wait() {
+                let _ = sender.send(StreamEvent::Exited(status));
+            }
+        });
+
+        Ok(CommandStream { receiver })
+    }
+
+    /// Execute a command string with validation
+    ///
+    /// This is the main entry point for safe command execution
+    pub fn execute(&self, command: &str) -> Result<std::process::Output> {
+        let parsed = self.parse_and_validate(command)?;
+        self.execute_safe(&parsed)
+    }
+
+    /// Run `base` once per batch of `items`, xargs-style
+    ///
+    /// Appends each item to `base`'s argv (or substitutes it into
+    /// `options.replace_placeholder`, if set) and splits `items` into as
+    /// many invocations as needed so no single argv exceeds
+    /// `options.byte_budget` or `options.max_args_per_call`. Every generated
+    /// argv is passed through `validate()` before execution, so callers
+    /// that need to fan a whitelisted command out over many items (e.g.
+    /// `usermod` across dozens of users) don't have to loop and
+    /// re-validate by hand.
+    pub fn execute_batch(
+        &self,
+        base: &ParsedCommand,
+        items: &[String],
+        options: &BatchOptions,
+    ) -> Result<Vec<std::process::Output>> {
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let env_bytes = self.effective_env_byte_size();
+        let fixed_bytes = argv_byte_size(&base.executable, &base.arguments);
+
+        let mut outputs = Vec::new();
+        let mut batch: Vec<String> = Vec::new();
+        let mut batch_bytes = fixed_bytes + env_bytes;
+
+        for item in items {
+            let item_bytes = item.len() + 1; // + NUL terminator
+
+            let exceeds_budget = batch_bytes + item_bytes > options.byte_budget;
+            let exceeds_count = matches!(options.max_args_per_call, Some(max) if batch.len() >= max);
+            let one_item_per_call = options.replace_placeholder.is_some();
+
+            if !batch.is_empty() && (exceeds_budget || exceeds_count || one_item_per_call) {
+                outputs.push(self.run_batch(base, &batch, options)?);
+                batch.clear();
+                batch_bytes = fixed_bytes + env_bytes;
+            }
+
+            batch.push(item.clone());
+            batch_bytes += item_bytes;
+        }
+
+        if !batch.is_empty() {
+            outputs.push(self.run_batch(base, &batch, options)?);
+        }
+
+        Ok(outputs)
+    }
+
+    fn run_batch(
+        &self,
+        base: &ParsedCommand,
+        batch: &[String],
+        options: &BatchOptions,
+    ) -> Result<std::process::Output> {
+        let command = build_batch_command(base, batch, options.replace_placeholder.as_deref());
+        self.validate(&command)?;
+        self.execute_safe(&command)
+    }
+
+    /// Estimate the byte size of the environment `apply_env_to` would
+    /// produce for a spawned child, for budgeting purposes in
+    /// `execute_batch`.
+    fn effective_env_byte_size(&self) -> usize {
+        let inherited: usize = match &self.env_mode {
+            EnvMode::Inherit => std::env::vars().map(|(k, v)| k.len() + v.len() + 2).sum(),
+            EnvMode::Clear => 0,
+            EnvMode::AllowList(keys) => keys
+                .iter()
+                .filter_map(|k| std::env::var(k).ok().map(|v| k.len() + v.len() + 2))
+                .sum(),
+        };
+        let overrides: usize = self
+            .env_overrides
+            .iter()
+            .map(|(k, v)| k.len() + v.len() + 2)
+            .sum();
+        inherited + overrides
+    }
+}
+
+/// Every `CString` [`Sandbox::apply`] needs inside its post-fork `pre_exec`
+/// closure, allocated up front (before `fork()`) so the closure itself only
+/// touches already-allocated, null-terminated buffers and raw libc calls -
+/// `CString::new` itself is not async-signal-safe.
+#[cfg(target_os = "linux")]
+struct SandboxPrepared {
+    root_dir: Option<std::ffi::CString>,
+    mount_root: std::ffi::CString,
+    mount_tmpfs: std::ffi::CString,
+    mount_tmp: std::ffi::CString,
+    chdir_root: std::ffi::CString,
+}
+
+#[cfg(target_os = "linux")]
+impl Sandbox {
+    /// Catch unsupported combinations before we fork, so callers get a
+    /// normal `Result` error instead of a child that dies silently in
+    /// `pre_exec`.
+    fn validate_supported(&self) -> Result<()> {
+        if self.root_dir.is_some() && !self.new_mount_namespace {
+            return Err(S1bCr4ftError::package(
+                "Sandbox::chroot requires new_mount_namespace()".to_string(),
+            ));
+        }
+        if self.readonly_root_with_tmpfs_tmp && !self.new_mount_namespace {
+            return Err(S1bCr4ftError::package(
+                "Sandbox::readonly_root_with_tmpfs_tmp requires new_mount_namespace()".to_string(),
+            ));
+        }
+        for name in &self.seccomp_allowlist {
+            if syscall_number(name).is_none() {
+                return Err(S1bCr4ftError::package(format!(
+                    "Unknown syscall in seccomp allow-list: {}",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Allocate every `CString` [`Self::apply`] will need, before `fork()`.
+    fn prepare(&self) -> std::io::Result<SandboxPrepared> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let root_dir = self
+            .root_dir
+            .as_ref()
+            .map(|root| {
+                CString::new(root.as_os_str().as_bytes()).map_err(|_| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "root path contains NUL")
+                })
             })
+            .transpose()?;
+
+        Ok(SandboxPrepared {
+            root_dir,
+            mount_root: CString::new("/").unwrap(),
+            mount_tmpfs: CString::new("tmpfs").unwrap(),
+            mount_tmp: CString::new("/tmp").unwrap(),
+            chdir_root: CString::new("/").unwrap(),
+        })
+    }
+
+    /// Runs inside the forked child, after `fork()` but before `exec()`.
+    /// Every step here must be async-signal-safe: no heap allocation beyond
+    /// what glibc's allocator itself guarantees safe post-fork, no locking,
+    /// only raw syscalls - so we stick to `libc` calls throughout, and rely
+    /// on `prepared` for every `CString` instead of building any here.
+    fn apply(&self, prepared: &SandboxPrepared) -> std::io::Result<()> {
+        if self.new_user_namespace || self.new_mount_namespace || self.new_pid_namespace {
+            let mut flags = 0;
+            if self.new_user_namespace {
+                flags |= libc::CLONE_NEWUSER;
+            }
+            if self.new_mount_namespace {
+                flags |= libc::CLONE_NEWNS;
+            }
+            if self.new_pid_namespace {
+                flags |= libc::CLONE_NEWPID;
+            }
+            if unsafe { libc::unshare(flags) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        if self.readonly_root_with_tmpfs_tmp {
+            mount_readonly_root_with_tmpfs_tmp(prepared)?;
+        }
+
+        if prepared.root_dir.is_some() {
+            pivot_into(prepared)?;
+        }
+
+        for limit in &self.rlimits {
+            apply_rlimit(*limit)?;
+        }
+
+        if let Some(gid) = self.gid {
+            if unsafe { libc::setgroups(0, std::ptr::null()) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if unsafe { libc::setresgid(gid, gid, gid) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+        if let Some(uid) = self.uid {
+            if unsafe { libc::setresuid(uid, uid, uid) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+
+        if !self.seccomp_allowlist.is_empty() {
+            install_seccomp_filter(&self.seccomp_allowlist, self.seccomp_violation_action)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn mount_readonly_root_with_tmpfs_tmp(prepared: &SandboxPrepared) -> std::io::Result<()> {
+    let none: *const libc::c_char = std::ptr::null();
+
+    unsafe {
+        if libc::mount(
+            none,
+            prepared.mount_root.as_ptr(),
+            none,
+            libc::MS_REMOUNT | libc::MS_RDONLY | libc::MS_BIND,
+            std::ptr::null(),
+        ) != 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+        if libc::mount(
+            prepared.mount_tmpfs.as_ptr(),
+            prepared.mount_tmp.as_ptr(),
+            prepared.mount_tmpfs.as_ptr(),
+            0,
+            std::ptr::null(),
+        ) != 0
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn pivot_into(prepared: &SandboxPrepared) -> std::io::Result<()> {
+    // `Sandbox::apply` only calls this when `prepared.root_dir` is `Some`.
+    let root_c = prepared
+        .root_dir
+        .as_ref()
+        .expect("pivot_into called without a prepared root_dir");
+
+    unsafe {
+        if libc::chroot(root_c.as_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        if libc::chdir(prepared.chdir_root.as_ptr()) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn apply_rlimit(limit: SandboxRlimit) -> std::io::Result<()> {
+    let (resource, value) = match limit {
+        SandboxRlimit::AddressSpace(bytes) => (libc::RLIMIT_AS, bytes),
+        SandboxRlimit::NumProcesses(n) => (libc::RLIMIT_NPROC, n),
+        SandboxRlimit::FileSize(bytes) => (libc::RLIMIT_FSIZE, bytes),
+        SandboxRlimit::NumFiles(n) => (libc::RLIMIT_NOFILE, n),
+    };
+    let rlim = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    if unsafe { libc::setrlimit(resource, &rlim) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Map a syscall name to its number on this architecture. Covers the
+/// syscalls a module's validated commands are realistically expected to
+/// need; extend as sandboxed commands require more.
+#[cfg(target_os = "linux")]
+fn syscall_number(name: &str) -> Option<i64> {
+    Some(match name {
+        "read" => libc::SYS_read,
+        "write" => libc::SYS_write,
+        "open" => libc::SYS_open,
+        "openat" => libc::SYS_openat,
+        "close" => libc::SYS_close,
+        "stat" => libc::SYS_stat,
+        "fstat" => libc::SYS_fstat,
+        "lstat" => libc::SYS_lstat,
+        "mmap" => libc::SYS_mmap,
+        "munmap" => libc::SYS_munmap,
+        "mprotect" => libc::SYS_mprotect,
+        "brk" => libc::SYS_brk,
+        "rt_sigaction" => libc::SYS_rt_sigaction,
+        "rt_sigprocmask" => libc::SYS_rt_sigprocmask,
+        "rt_sigreturn" => libc::SYS_rt_sigreturn,
+        "ioctl" => libc::SYS_ioctl,
+        "access" => libc::SYS_access,
+        "pipe" => libc::SYS_pipe,
+        "dup" => libc::SYS_dup,
+        "dup2" => libc::SYS_dup2,
+        "nanosleep" => libc::SYS_nanosleep,
+        "getpid" => libc::SYS_getpid,
+        "exit" => libc::SYS_exit,
+        "exit_group" => libc::SYS_exit_group,
+        "execve" => libc::SYS_execve,
+        "wait4" => libc::SYS_wait4,
+        "clone" => libc::SYS_clone,
+        "gettimeofday" => libc::SYS_gettimeofday,
+        "clock_gettime" => libc::SYS_clock_gettime,
+        "futex" => libc::SYS_futex,
+        "set_tid_address" => libc::SYS_set_tid_address,
+        "set_robust_list" => libc::SYS_set_robust_list,
+        "arch_prctl" => libc::SYS_arch_prctl,
+        "readlink" => libc::SYS_readlink,
+        "getdents64" => libc::SYS_getdents64,
+        "lseek" => libc::SYS_lseek,
+        "unlink" => libc::SYS_unlink,
+        "mkdir" => libc::SYS_mkdir,
+        "chdir" => libc::SYS_chdir,
+        "fcntl" => libc::SYS_fcntl,
+        "getrandom" => libc::SYS_getrandom,
+        "prlimit64" => libc::SYS_prlimit64,
+        _ => return None,
+    })
+}
+
+/// Install a default-deny seccomp-bpf filter: every syscall in `allowed` is
+/// permitted, everything else triggers `on_violation`. Mirrors minijail's
+/// `-S`/syscall-filter behavior using a hand-built classic BPF program,
+/// since that's the only ABI the kernel accepts via `PR_SET_SECCOMP`.
+#[cfg(target_os = "linux")]
+fn install_seccomp_filter(
+    allowed: &[String],
+    on_violation: SeccompViolationAction,
+) -> std::io::Result<()> {
+    let numbers: Vec<i64> = allowed.iter().filter_map(|name| syscall_number(name)).collect();
+
+    let deny_action: u32 = match on_violation {
+        SeccompViolationAction::Trap => libc::SECCOMP_RET_TRAP,
+        SeccompViolationAction::ReturnError => {
+            libc::SECCOMP_RET_ERRNO | (libc::EPERM as u32 & libc::SECCOMP_RET_DATA)
+        }
+    };
+
+    // offsetof(struct seccomp_data, nr) is always 0: `nr` is the struct's
+    // first field.
+    let mut program = Vec::with_capacity(numbers.len() + 3);
+    program.push(libc::sock_filter {
+        code: (libc::BPF_LD | libc::BPF_W | libc::BPF_ABS) as u16,
+        jt: 0,
+        jf: 0,
+        k: 0,
+    });
+
+    let allowed_count = numbers.len() as u8;
+    for (i, nr) in numbers.iter().enumerate() {
+        program.push(libc::sock_filter {
+            code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+            // Jump forward past the remaining checks straight to ALLOW on a
+            // match; fall through to the next check (or DENY) otherwise.
+            jt: allowed_count - i as u8,
+            jf: 0,
+            k: *nr as u32,
+        });
+    }
+
+    program.push(libc::sock_filter {
+        code: (libc::BPF_RET | libc::BPF_K) as u16,
+        jt: 0,
+        jf: 0,
+        k: deny_action,
+    });
+    program.push(libc::sock_filter {
+        code: (libc::BPF_RET | libc::BPF_K) as u16,
+        jt: 0,
+        jf: 0,
+        k: libc::SECCOMP_RET_ALLOW,
+    });
+
+    let mut fprog = libc::sock_fprog {
+        len: program.len() as u16,
+        filter: program.as_mut_ptr(),
+    };
+
+    unsafe {
+        // Required to install a filter without CAP_SYS_ADMIN.
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        if libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER, &mut fprog) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// Tokenizer state for [`tokenize`]. Kept explicit (rather than the
+/// previous ad-hoc `in_single_quote`/`in_double_quote`/`escaped` booleans)
+/// so each quoting rule is auditable on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenizerMode {
+    Unquoted,
+    Single,
+    Double,
+    AnsiC,
+}
+
+/// Split a command string into words, POSIX-shell style (quoting and
+/// escaping only - no globbing, variable expansion, or command
+/// substitution). See [`CommandValidator::parse`] for the exact rules.
+fn tokenize(input: &str) -> Result<Vec<String>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut mode = TokenizerMode::Unquoted;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match mode {
+            TokenizerMode::Unquoted => match c {
+                ' ' | '\t' => {
+                    if in_word {
+                        parts.push(std::mem::take(&mut current));
+                        in_word = false;
+                    }
+                    i += 1;
+                }
+                '\'' => {
+                    mode = TokenizerMode::Single;
+                    in_word = true;
+                    i += 1;
+                }
+                '"' => {
+                    mode = TokenizerMode::Double;
+                    in_word = true;
+                    i += 1;
+                }
+                '$' if chars.get(i + 1) == Some(&'\'') => {
+                    mode = TokenizerMode::AnsiC;
+                    in_word = true;
+                    i += 2;
+                }
+                '\\' => {
+                    i += 1;
+                    if let Some(&next) = chars.get(i) {
+                        current.push(next);
+                        in_word = true;
+                        i += 1;
+                    }
+                    // A lone trailing backslash at EOF has nothing to
+                    // escape; it's simply dropped.
+                }
+                _ => {
+                    current.push(c);
+                    in_word = true;
+                    i += 1;
+                }
+            },
+            TokenizerMode::Single => match c {
+                '\'' => {
+                    mode = TokenizerMode::Unquoted;
+                    i += 1;
+                }
+                _ => {
+                    // Backslash is literal inside single quotes.
+                    current.push(c);
+                    i += 1;
+                }
+            },
+            TokenizerMode::Double => match c {
+                '"' => {
+                    mode = TokenizerMode::Unquoted;
+                    i += 1;
+                }
+                '\\' => match chars.get(i + 1) {
+                    Some(&next) if matches!(next, '"' | '\\' | '`' | '$') => {
+                        current.push(next);
+                        i += 2;
+                    }
+                    Some(&next) => {
+                        // Backslash isn't special before any other
+                        // character inside double quotes; keep both.
+                        current.push('\\');
+                        current.push(next);
+                        i += 2;
+                    }
+                    None => {
+                        return Err(S1bCr4ftError::package(
+                            "Unterminated double quote in command".to_string(),
+                        ));
+                    }
+                },
+                _ => {
+                    current.push(c);
+                    i += 1;
+                }
+            },
+            TokenizerMode::AnsiC => match c {
+                '\'' => {
+                    mode = TokenizerMode::Unquoted;
+                    i += 1;
+                }
+                '\\' => {
+                    i += 1;
+                    match chars.get(i) {
+                        Some('n') => {
+                            current.push('\n');
+                            i += 1;
+                        }
+                        Some('t') => {
+                            current.push('\t');
+                            i += 1;
+                        }
+                        Some('r') => {
+                            current.push('\r');
+                            i += 1;
+                        }
+                        Some('\\') => {
+                            current.push('\\');
+                            i += 1;
+                        }
+                        Some('\'') => {
+                            current.push('\'');
+                            i += 1;
+                        }
+                        Some('x') => {
+                            i += 1;
+                            let (byte, consumed) = read_radix_escape(&chars[i..], 16, 2);
+                            current.push(byte as char);
+                            i += consumed;
+                        }
+                        Some('0') => {
+                            i += 1;
+                            let (byte, consumed) = read_radix_escape(&chars[i..], 8, 3);
+                            current.push(byte as char);
+                            i += consumed;
+                        }
+                        Some(&other) => {
+                            // Unrecognized escape: bash leaves both
+                            // characters as-is.
+                            current.push('\\');
+                            current.push(other);
+                            i += 1;
+                        }
+                        None => {
+                            return Err(S1bCr4ftError::package(
+                                "Unterminated $'...' quote in command".to_string(),
+                            ));
+                        }
+                    }
+                }
+                _ => {
+                    current.push(c);
+                    i += 1;
+                }
+            },
+        }
+    }
+
+    match mode {
+        TokenizerMode::Unquoted => {}
+        TokenizerMode::Single => {
+            return Err(S1bCr4ftError::package(
+                "Unterminated single quote in command".to_string(),
+            ))
+        }
+        TokenizerMode::Double => {
+            return Err(S1bCr4ftError::package(
+                "Unterminated double quote in command".to_string(),
+            ))
+        }
+        TokenizerMode::AnsiC => {
+            return Err(S1bCr4ftError::package(
+                "Unterminated $'...' quote in command".to_string(),
+            ))
+        }
     }
 
-    /// Execute a command string with validation
-    ///
-    /// This is the main entry point for safe command execution
-    pub fn execute(&self, command: &str) -> Result<std::process::Output> {
-        let parsed = self.parse_and_validate(command)?;
-        self.execute_safe(&parsed)
+    if in_word {
+        parts.push(current);
+    }
+
+    Ok(parts)
+}
+
+/// Read up to `max_digits` digits of `radix` from the front of `chars`,
+/// returning the parsed byte value and how many characters were consumed.
+/// Used for ANSI-C `\xHH`/`\0NNN` escapes; an escape with no valid digits
+/// yields `(0, 0)`.
+fn read_radix_escape(chars: &[char], radix: u32, max_digits: usize) -> (u8, usize) {
+    let mut value: u32 = 0;
+    let mut consumed = 0;
+    for &c in chars.iter().take(max_digits) {
+        match c.to_digit(radix) {
+            Some(d) => {
+                value = value * radix + d;
+                consumed += 1;
+            }
+            None => break,
+        }
     }
+    (value as u8, consumed)
 }
 
 /// Check if a character is safe in an executable name
@@ -566,4 +2144,614 @@ mod tests {
         let parsed = validator.parse("echo \"it's\" 'test'").unwrap();
         assert_eq!(parsed.arguments, vec!["it's", "test"]);
     }
+
+    #[test]
+    fn test_sandbox_default_is_unconfined() {
+        let sandbox = Sandbox::new();
+        assert_eq!(sandbox, Sandbox::default());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sandbox_chroot_without_mount_namespace_rejected() {
+        let sandbox = Sandbox::new().chroot("/tmp/sandbox-root");
+        assert!(sandbox.validate_supported().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sandbox_chroot_with_mount_namespace_accepted() {
+        let sandbox = Sandbox::new()
+            .new_mount_namespace()
+            .chroot("/tmp/sandbox-root");
+        assert!(sandbox.validate_supported().is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sandbox_readonly_root_without_mount_namespace_rejected() {
+        let sandbox = Sandbox::new().readonly_root_with_tmpfs_tmp();
+        assert!(sandbox.validate_supported().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sandbox_unknown_syscall_rejected() {
+        let sandbox = Sandbox::new().seccomp_allow(
+            vec!["read", "not_a_real_syscall"],
+            SeccompViolationAction::Trap,
+        );
+        assert!(sandbox.validate_supported().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sandbox_known_syscalls_accepted() {
+        let sandbox = Sandbox::new().seccomp_allow(
+            vec!["read", "write", "exit_group"],
+            SeccompViolationAction::ReturnError,
+        );
+        assert!(sandbox.validate_supported().is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_execute_sandboxed_rejects_unsupported_chroot() {
+        let validator = CommandValidator::new();
+        let parsed = ParsedCommand {
+            executable: "systemctl".to_string(),
+            arguments: vec!["status".to_string()],
+        };
+        let sandbox = Sandbox::new().chroot("/tmp/sandbox-root");
+        assert!(validator.execute_sandboxed(&parsed, &sandbox).is_err());
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    #[test]
+    fn test_execute_sandboxed_unsupported_off_linux() {
+        let validator = CommandValidator::new();
+        let parsed = ParsedCommand {
+            executable: "systemctl".to_string(),
+            arguments: vec!["status".to_string()],
+        };
+        assert!(validator
+            .execute_sandboxed(&parsed, &Sandbox::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_utf8_chunk_decoder_whole_chunk() {
+        let mut decoder = Utf8ChunkDecoder::new();
+        let chunk = decoder.decode("hello world".as_bytes()).unwrap();
+        assert_eq!(chunk, Chunk::Text("hello world".to_string()));
+    }
+
+    #[test]
+    fn test_utf8_chunk_decoder_holds_back_split_multibyte_char() {
+        let snowman = "\u{2603}".as_bytes(); // 3-byte UTF-8 sequence
+        let mut decoder = Utf8ChunkDecoder::new();
+
+        // Feed everything but the last byte: the decoder should hold the
+        // incomplete sequence back rather than reporting it as binary.
+        assert_eq!(decoder.decode(&snowman[..snowman.len() - 1]), None);
+
+        let chunk = decoder.decode(&snowman[snowman.len() - 1..]).unwrap();
+        assert_eq!(chunk, Chunk::Text("\u{2603}".to_string()));
+    }
+
+    #[test]
+    fn test_utf8_chunk_decoder_emits_binary_for_invalid_bytes() {
+        let mut decoder = Utf8ChunkDecoder::new();
+        let chunk = decoder.decode(&[0xff, 0xfe, 0x00]).unwrap();
+        assert_eq!(chunk, Chunk::Binary(vec![0xff, 0xfe, 0x00]));
+    }
+
+    #[test]
+    fn test_utf8_chunk_decoder_flush_emits_incomplete_tail_as_binary() {
+        let snowman = "\u{2603}".as_bytes();
+        let mut decoder = Utf8ChunkDecoder::new();
+        assert_eq!(decoder.decode(&snowman[..snowman.len() - 1]), None);
+
+        let flushed = decoder.flush().unwrap();
+        assert_eq!(flushed, Chunk::Binary(snowman[..snowman.len() - 1].to_vec()));
+    }
+
+    #[test]
+    fn test_execute_streaming_collects_text_chunks_and_exit_status() {
+        let validator = CommandValidator::with_whitelist(vec!["echo".to_string()]);
+        let parsed = ParsedCommand {
+            executable: "echo".to_string(),
+            arguments: vec!["hello".to_string()],
+        };
+
+        let stream = validator.execute_streaming(&parsed).unwrap();
+        let events: Vec<StreamEvent> = stream.collect();
+
+        let exited = events
+            .iter()
+            .filter(|e| matches!(e, StreamEvent::Exited(_)))
+            .count();
+        assert_eq!(exited, 1);
+        assert!(matches!(events.last(), Some(StreamEvent::Exited(_))));
+
+        let stdout_text: String = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::Output {
+                    source: StreamSource::Stdout,
+                    chunk: Chunk::Text(text),
+                } => Some(text.clone()),
+                _ => None,
+            })
+            .collect();
+        assert!(stdout_text.contains("hello"));
+    }
+
+    #[test]
+    fn test_signal_parse_by_number() {
+        assert_eq!(Signal::parse("15").unwrap(), Signal::from_number(15));
+    }
+
+    #[test]
+    fn test_signal_parse_by_bare_name() {
+        assert_eq!(Signal::parse("TERM").unwrap(), Signal::from_number(libc::SIGTERM));
+    }
+
+    #[test]
+    fn test_signal_parse_by_sig_prefixed_name() {
+        assert_eq!(Signal::parse("SIGTERM").unwrap(), Signal::from_number(libc::SIGTERM));
+        assert_eq!(Signal::parse("SIGHUP").unwrap(), Signal::from_number(libc::SIGHUP));
+    }
+
+    #[test]
+    fn test_signal_parse_unknown_name_rejected() {
+        assert!(Signal::parse("NOTASIGNAL").is_err());
+    }
+
+    #[test]
+    fn test_signal_kill_and_stop_are_fixed() {
+        assert!(Signal::from_number(libc::SIGKILL).is_fixed());
+        assert!(Signal::from_number(libc::SIGSTOP).is_fixed());
+        assert!(!Signal::from_number(libc::SIGTERM).is_fixed());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_signal_dispositions_skips_kill_and_stop() {
+        // SIGKILL/SIGSTOP can't have their disposition changed; this must
+        // not return an error for them.
+        let result = apply_signal_dispositions(
+            &[Signal::from_number(libc::SIGKILL)],
+            &[Signal::from_number(libc::SIGSTOP)],
+        );
+        assert!(result.is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_safe_with_ignored_signal_runs_command() {
+        let validator = CommandValidator::with_whitelist(vec!["echo".to_string()])
+            .ignore_signals(&[Signal::parse("SIGTERM").unwrap()]);
+        let parsed = ParsedCommand {
+            executable: "echo".to_string(),
+            arguments: vec!["hi".to_string()],
+        };
+        let output = validator.execute_safe(&parsed).unwrap();
+        assert!(output.status.success());
+    }
+
+    #[test]
+    fn test_validate_env_key_accepts_safe_names() {
+        assert!(validate_env_key("PATH").is_ok());
+        assert!(validate_env_key("_internal").is_ok());
+        assert!(validate_env_key("FOO_BAR_2").is_ok());
+    }
+
+    #[test]
+    fn test_validate_env_key_rejects_unsafe_names() {
+        assert!(validate_env_key("").is_err());
+        assert!(validate_env_key("2FOO").is_err());
+        assert!(validate_env_key("FOO=BAR").is_err());
+        assert!(validate_env_key("FOO BAR").is_err());
+    }
+
+    #[test]
+    fn test_set_env_rejects_invalid_key() {
+        let result = CommandValidator::new().set_env("2INVALID", "x");
+        assert!(result.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_safe_with_cleared_env_only_sees_explicit_vars() {
+        let validator = CommandValidator::with_whitelist(vec!["env".to_string()])
+            .clear_env()
+            .set_env("S1BCR4FT_TEST_VAR", "hello")
+            .unwrap();
+        let parsed = ParsedCommand {
+            executable: "env".to_string(),
+            arguments: vec![],
+        };
+        let output = validator.execute_safe(&parsed).unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.trim(), "S1BCR4FT_TEST_VAR=hello");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_safe_with_allow_env_passes_through_only_named_vars() {
+        std::env::set_var("S1BCR4FT_ALLOWED_VAR", "visible");
+        std::env::set_var("S1BCR4FT_HIDDEN_VAR", "invisible");
+
+        let validator = CommandValidator::with_whitelist(vec!["env".to_string()])
+            .allow_env(&["S1BCR4FT_ALLOWED_VAR"]);
+        let parsed = ParsedCommand {
+            executable: "env".to_string(),
+            arguments: vec![],
+        };
+        let output = validator.execute_safe(&parsed).unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        std::env::remove_var("S1BCR4FT_ALLOWED_VAR");
+        std::env::remove_var("S1BCR4FT_HIDDEN_VAR");
+
+        assert!(stdout.contains("S1BCR4FT_ALLOWED_VAR=visible"));
+        assert!(!stdout.contains("S1BCR4FT_HIDDEN_VAR"));
+    }
+
+    #[test]
+    fn test_trusted_path_resolves_against_given_directories() {
+        let temp_dir = std::env::temp_dir().join("s1bcr4ft_test_trusted_path");
+        std::fs::create_dir_all(&temp_dir).unwrap();
+        let fake_bin = temp_dir.join("systemctl");
+        std::fs::write(&fake_bin, "#!/bin/sh\nexit 0\n").unwrap();
+
+        let validator = CommandValidator::new().trusted_path(vec![temp_dir.clone()]);
+        let resolved = validator.resolve_executable("systemctl").unwrap();
+        assert_eq!(resolved, fake_bin);
+
+        std::fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_trusted_path_rejects_missing_executable() {
+        let validator = CommandValidator::new().trusted_path(vec![PathBuf::from("/nonexistent")]);
+        assert!(validator.resolve_executable("systemctl").is_err());
+    }
+
+    #[test]
+    fn test_execute_batch_empty_items_returns_empty() {
+        let validator = CommandValidator::with_whitelist(vec!["usermod".to_string()]);
+        let base = ParsedCommand {
+            executable: "usermod".to_string(),
+            arguments: vec!["-aG".to_string(), "wheel".to_string()],
+        };
+        let outputs = validator
+            .execute_batch(&base, &[], &BatchOptions::default())
+            .unwrap();
+        assert!(outputs.is_empty());
+    }
+
+    #[test]
+    fn test_execute_batch_splits_by_byte_budget() {
+        // The environment is cleared so it adds no bytes to each call's size.
+        let validator = CommandValidator::with_whitelist(vec!["echo".to_string()]).clear_env();
+        let base = ParsedCommand {
+            executable: "echo".to_string(),
+            arguments: vec![],
+        };
+        let items: Vec<String> = (0..10).map(|i| format!("user{}", i)).collect();
+
+        // Budget big enough for the executable plus exactly two items.
+        let options = BatchOptions {
+            byte_budget: argv_byte_size("echo", &[]) + 2 * ("user0".len() + 1),
+            ..BatchOptions::default()
+        };
+        let outputs = validator.execute_batch(&base, &items, &options).unwrap();
+
+        // 10 items at 2 per call == 5 calls.
+        assert_eq!(outputs.len(), 5);
+        for output in &outputs {
+            assert!(output.status.success());
+        }
+    }
+
+    #[test]
+    fn test_execute_batch_respects_max_args_per_call() {
+        let validator = CommandValidator::with_whitelist(vec!["echo".to_string()]);
+        let base = ParsedCommand {
+            executable: "echo".to_string(),
+            arguments: vec![],
+        };
+        let items: Vec<String> = (0..9).map(|i| format!("item{}", i)).collect();
+        let options = BatchOptions {
+            max_args_per_call: Some(3),
+            ..BatchOptions::default()
+        };
+        let outputs = validator.execute_batch(&base, &items, &options).unwrap();
+        assert_eq!(outputs.len(), 3);
+    }
+
+    #[test]
+    fn test_execute_batch_with_replacement_placeholder_runs_one_item_per_call() {
+        let validator = CommandValidator::with_whitelist(vec!["echo".to_string()]);
+        let base = ParsedCommand {
+            executable: "echo".to_string(),
+            arguments: vec!["item={}".to_string()],
+        };
+        let items = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let options = BatchOptions {
+            replace_placeholder: Some("{}".to_string()),
+            ..BatchOptions::default()
+        };
+        let outputs = validator.execute_batch(&base, &items, &options).unwrap();
+        assert_eq!(outputs.len(), 3);
+
+        let stdout = String::from_utf8_lossy(&outputs[0].stdout);
+        assert_eq!(stdout.trim(), "item=a");
+    }
+
+    #[test]
+    fn test_execute_batch_rejects_argv_that_fails_validation() {
+        let validator = CommandValidator::with_whitelist(vec!["systemctl".to_string()]);
+        let base = ParsedCommand {
+            executable: "systemctl".to_string(),
+            arguments: vec!["enable".to_string()],
+        };
+        let items = vec!["ok-unit".to_string(), "bad;unit".to_string()];
+        let result = validator.execute_batch(&base, &items, &BatchOptions::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_batch_command_appends_by_default() {
+        let base = ParsedCommand {
+            executable: "usermod".to_string(),
+            arguments: vec!["-aG".to_string(), "wheel".to_string()],
+        };
+        let command = build_batch_command(&base, &["alice".to_string(), "bob".to_string()], None);
+        assert_eq!(command.arguments, vec!["-aG", "wheel", "alice", "bob"]);
+    }
+
+    #[test]
+    fn test_build_batch_command_substitutes_placeholder() {
+        let base = ParsedCommand {
+            executable: "systemctl".to_string(),
+            arguments: vec!["enable".to_string(), "{}".to_string()],
+        };
+        let command = build_batch_command(&base, &["sshd.service".to_string()], Some("{}"));
+        assert_eq!(command.arguments, vec!["enable", "sshd.service"]);
+    }
+
+    #[test]
+    fn test_tokenize_concatenates_quoted_and_unquoted_runs() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo --opt=\"a b\"").unwrap();
+        assert_eq!(parsed.arguments, vec!["--opt=a b"]);
+    }
+
+    #[test]
+    fn test_tokenize_adjacent_quotes_concatenate_into_one_word() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo a\"b\"c").unwrap();
+        assert_eq!(parsed.arguments, vec!["abc"]);
+    }
+
+    #[test]
+    fn test_tokenize_backslash_is_literal_inside_single_quotes() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo 'a\\nb'").unwrap();
+        assert_eq!(parsed.arguments, vec!["a\\nb"]);
+    }
+
+    #[test]
+    fn test_tokenize_double_quote_escapes() {
+        let validator = CommandValidator::new();
+        let parsed = validator
+            .parse("echo \"say \\\"hi\\\" and \\$5 \\\\ \\`x\\`\"")
+            .unwrap();
+        assert_eq!(parsed.arguments, vec!["say \"hi\" and $5 \\ `x`"]);
+    }
+
+    #[test]
+    fn test_tokenize_double_quote_backslash_not_special_before_other_chars() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo \"a\\nb\"").unwrap();
+        assert_eq!(parsed.arguments, vec!["a\\nb"]);
+    }
+
+    #[test]
+    fn test_tokenize_ansi_c_quoting_basic_escapes() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo $'line1\\nline2\\ttab'").unwrap();
+        assert_eq!(parsed.arguments, vec!["line1\nline2\ttab"]);
+    }
+
+    #[test]
+    fn test_tokenize_ansi_c_hex_escape() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo $'\\x41\\x42'").unwrap();
+        assert_eq!(parsed.arguments, vec!["AB"]);
+    }
+
+    #[test]
+    fn test_tokenize_ansi_c_octal_escape() {
+        let validator = CommandValidator::new();
+        let parsed = validator.parse("echo $'\\0101\\0102'").unwrap();
+        assert_eq!(parsed.arguments, vec!["AB"]);
+    }
+
+    #[test]
+    fn test_tokenize_unterminated_single_quote_errors() {
+        let validator = CommandValidator::new();
+        assert!(validator.parse("echo 'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_tokenize_unterminated_double_quote_errors() {
+        let validator = CommandValidator::new();
+        assert!(validator.parse("echo \"unterminated").is_err());
+    }
+
+    #[test]
+    fn test_tokenize_unterminated_ansi_c_quote_errors() {
+        let validator = CommandValidator::new();
+        assert!(validator.parse("echo $'unterminated").is_err());
+    }
+
+    #[test]
+    fn test_read_radix_escape_stops_at_non_digit() {
+        let chars: Vec<char> = "41zz".chars().collect();
+        assert_eq!(read_radix_escape(&chars, 16, 2), (0x41, 2));
+    }
+
+    #[test]
+    fn test_read_radix_escape_no_digits_yields_zero() {
+        let chars: Vec<char> = "zz".chars().collect();
+        assert_eq!(read_radix_escape(&chars, 16, 2), (0, 0));
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_bare_ident() {
+        assert_eq!(
+            CfgExpr::parse("glibc").unwrap(),
+            CfgExpr::Ident("glibc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_key_value() {
+        assert_eq!(
+            CfgExpr::parse("init = \"systemd\"").unwrap(),
+            CfgExpr::KeyValue("init".to_string(), "systemd".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_all() {
+        assert_eq!(
+            CfgExpr::parse("all(target_os = \"linux\", init = \"systemd\")").unwrap(),
+            CfgExpr::All(vec![
+                CfgExpr::KeyValue("target_os".to_string(), "linux".to_string()),
+                CfgExpr::KeyValue("init".to_string(), "systemd".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_any() {
+        assert_eq!(
+            CfgExpr::parse("any(musl, glibc)").unwrap(),
+            CfgExpr::Any(vec![
+                CfgExpr::Ident("musl".to_string()),
+                CfgExpr::Ident("glibc".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_not() {
+        assert_eq!(
+            CfgExpr::parse("not(systemd)").unwrap(),
+            CfgExpr::Not(Box::new(CfgExpr::Ident("systemd".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_nested() {
+        assert_eq!(
+            CfgExpr::parse("all(not(musl), any(init = \"systemd\", init = \"openrc\"))").unwrap(),
+            CfgExpr::All(vec![
+                CfgExpr::Not(Box::new(CfgExpr::Ident("musl".to_string()))),
+                CfgExpr::Any(vec![
+                    CfgExpr::KeyValue("init".to_string(), "systemd".to_string()),
+                    CfgExpr::KeyValue("init".to_string(), "openrc".to_string()),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_rejects_trailing_garbage() {
+        assert!(CfgExpr::parse("glibc extra").is_err());
+    }
+
+    #[test]
+    fn test_cfg_expr_parse_rejects_unterminated_list() {
+        assert!(CfgExpr::parse("all(glibc").is_err());
+    }
+
+    #[test]
+    fn test_cfg_expr_eval_ident() {
+        let mut config = HashMap::new();
+        config.insert("glibc".to_string(), "".to_string());
+        let expr = CfgExpr::Ident("glibc".to_string());
+        assert!(expr.eval(&config));
+        assert!(!expr.eval(&HashMap::new()));
+    }
+
+    #[test]
+    fn test_cfg_expr_eval_key_value() {
+        let mut config = HashMap::new();
+        config.insert("init".to_string(), "systemd".to_string());
+        let expr = CfgExpr::KeyValue("init".to_string(), "systemd".to_string());
+        assert!(expr.eval(&config));
+
+        let mismatched = CfgExpr::KeyValue("init".to_string(), "openrc".to_string());
+        assert!(!mismatched.eval(&config));
+    }
+
+    #[test]
+    fn test_cfg_expr_eval_all_any_not() {
+        let mut config = HashMap::new();
+        config.insert("target_os".to_string(), "linux".to_string());
+
+        let all_expr = CfgExpr::All(vec![
+            CfgExpr::KeyValue("target_os".to_string(), "linux".to_string()),
+            CfgExpr::Ident("arch".to_string()),
+        ]);
+        assert!(!all_expr.eval(&config));
+
+        let any_expr = CfgExpr::Any(vec![
+            CfgExpr::KeyValue("target_os".to_string(), "linux".to_string()),
+            CfgExpr::Ident("arch".to_string()),
+        ]);
+        assert!(any_expr.eval(&config));
+
+        let not_expr = CfgExpr::Not(Box::new(CfgExpr::Ident("arch".to_string())));
+        assert!(not_expr.eval(&config));
+    }
+
+    #[test]
+    fn test_validate_executable_rejects_unsatisfied_cfg_predicate() {
+        let validator = CommandValidator::with_whitelist(vec![(
+            "locale-gen",
+            CfgExpr::Ident("glibc".to_string()),
+        )
+            .into()]);
+        let result = validator.validate(&ParsedCommand {
+            executable: "locale-gen".to_string(),
+            arguments: vec![],
+        });
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("glibc"), "error should name the unsatisfied predicate: {err}");
+    }
+
+    #[test]
+    fn test_validate_executable_allows_satisfied_cfg_predicate() {
+        let mut platform_config = HashMap::new();
+        platform_config.insert("init".to_string(), "systemd".to_string());
+        let validator = CommandValidator::with_whitelist(vec![(
+            "timedatectl",
+            CfgExpr::KeyValue("init".to_string(), "systemd".to_string()),
+        )
+            .into()])
+        .with_platform_config(platform_config);
+
+        let result = validator.validate(&ParsedCommand {
+            executable: "timedatectl".to_string(),
+            arguments: vec!["set-ntp".to_string(), "true".to_string()],
+        });
+        assert!(result.is_ok());
+    }
 }
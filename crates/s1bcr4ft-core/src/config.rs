@@ -1,6 +1,6 @@
 use crate::error::{Result, S1bCr4ftError};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Main configuration structure
@@ -34,9 +34,62 @@ pub struct Config {
     /// Security settings
     #[serde(default)]
     pub security: SecuritySettings,
+
+    /// User-defined command shorthands, e.g. `up: sync --dry-run`.
+    #[serde(default)]
+    pub aliases: HashMap<String, AliasTokens>,
+
+    /// Named bundles of module IDs (e.g. `dev: [development/languages/rust,
+    /// development/languages/go]`) that can be referenced by name from
+    /// `modules` instead of spelling out every member module. Expanded by
+    /// [`crate::module::ModuleResolver`], which also allows one profile to
+    /// reference another.
+    #[serde(default)]
+    pub module_profiles: HashMap<String, Vec<String>>,
+
+    /// Other config files to merge underneath this one, relative to this
+    /// file's directory, so a setup can be split into a base plus overlays.
+    /// See [`ConfigLoader::load`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<IncludePaths>,
 }
 
+/// An `include:` value, accepting either a single path or an ordered list.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IncludePaths {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl IncludePaths {
+    pub fn paths(&self) -> Vec<String> {
+        match self {
+            IncludePaths::Single(path) => vec![path.clone()],
+            IncludePaths::List(paths) => paths.clone(),
+        }
+    }
+}
+
+/// An alias's replacement tokens, accepting either a single command string
+/// (split on whitespace) or an explicit argument list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AliasTokens {
+    Single(String),
+    List(Vec<String>),
+}
+
+impl AliasTokens {
+    pub fn tokens(&self) -> Vec<String> {
+        match self {
+            AliasTokens::Single(s) => s.split_whitespace().map(|t| t.to_string()).collect(),
+            AliasTokens::List(tokens) => tokens.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DotfileEntry {
     pub source: PathBuf,
     pub target: PathBuf,
@@ -83,7 +136,7 @@ impl Default for ConfigOptions {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecuritySettings {
     #[serde(default)]
     pub isolation_level: Option<String>,
@@ -96,6 +149,39 @@ pub struct SecuritySettings {
 
     #[serde(default)]
     pub gpg_signing: bool,
+
+    /// Module IDs exempted from the vet-criteria check during sync, e.g.
+    /// first-party modules that don't need upstream-source review.
+    #[serde(default)]
+    pub vet_exemptions: Vec<String>,
+
+    /// Criteria every non-exempt module must have a satisfying entry in
+    /// `audits.toml` for before `sync` will install it.
+    #[serde(default = "default_vet_criteria")]
+    pub required_vet_criteria: Vec<String>,
+
+    /// Whether `sync` also rejects individual packages that aren't vetted in
+    /// `package_audits.toml`, per [`crate::package::SyncOptions::require_vetted`].
+    #[serde(default = "default_true")]
+    pub require_vetted_packages: bool,
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        Self {
+            isolation_level: None,
+            network_isolation: false,
+            container_sandbox: None,
+            gpg_signing: false,
+            vet_exemptions: Vec::new(),
+            required_vet_criteria: default_vet_criteria(),
+            require_vetted_packages: true,
+        }
+    }
+}
+
+fn default_vet_criteria() -> Vec<String> {
+    vec!["safe-to-install".to_string()]
 }
 
 fn default_true() -> bool {
@@ -106,9 +192,33 @@ fn default_true() -> bool {
 pub struct ConfigLoader;
 
 impl ConfigLoader {
-    /// Load configuration from YAML file
+    /// Load configuration from YAML file, recursively resolving its
+    /// `include:` key (if any) depth-first: each included file is loaded and
+    /// merged in order first, then this file is merged on top so the
+    /// most-local file wins. Include paths are resolved relative to the
+    /// directory of the file declaring them.
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Config> {
-        let content = std::fs::read_to_string(path.as_ref())
+        let mut stack = HashSet::new();
+        Self::load_resolved(path.as_ref(), &mut stack)
+    }
+
+    fn load_resolved(path: &Path, stack: &mut HashSet<PathBuf>) -> Result<Config> {
+        let canonical = path.canonicalize().map_err(|e| {
+            S1bCr4ftError::config(format!(
+                "Failed to resolve config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        if !stack.insert(canonical.clone()) {
+            return Err(S1bCr4ftError::config(format!(
+                "Include cycle detected at {}",
+                path.display()
+            )));
+        }
+
+        let content = std::fs::read_to_string(path)
             .map_err(|e| S1bCr4ftError::config(format!("Failed to read config file: {}", e)))?;
 
         let config: Config = serde_yaml::from_str(&content)?;
@@ -121,7 +231,100 @@ impl ConfigLoader {
             )));
         }
 
-        Ok(config)
+        let resolved = match &config.include {
+            Some(includes) => {
+                let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+                let mut merged: Option<Config> = None;
+                for include_path in includes.paths() {
+                    let included = Self::load_resolved(&base_dir.join(include_path), stack)?;
+                    merged = Some(match merged {
+                        Some(acc) => Self::merge(acc, included),
+                        None => included,
+                    });
+                }
+                match merged {
+                    Some(acc) => Self::merge(acc, config),
+                    None => config,
+                }
+            }
+            None => config,
+        };
+
+        stack.remove(&canonical);
+        Ok(resolved)
+    }
+
+    /// Merge `overlay` on top of `base`: scalars are taken from `overlay`,
+    /// sequence fields are concatenated with de-duplication (first-seen
+    /// order preserved), and maps are merged key-by-key with `overlay`
+    /// winning on conflicts.
+    fn merge(base: Config, overlay: Config) -> Config {
+        Config {
+            version: overlay.version,
+            name: overlay.name,
+            description: overlay.description,
+            modules: Self::merge_dedup(base.modules, overlay.modules),
+            dotfiles: Self::merge_dedup(base.dotfiles, overlay.dotfiles),
+            hooks: Hooks {
+                pre_sync: overlay.hooks.pre_sync.or(base.hooks.pre_sync),
+                post_sync: overlay.hooks.post_sync.or(base.hooks.post_sync),
+                pre_module: overlay.hooks.pre_module.or(base.hooks.pre_module),
+                post_module: overlay.hooks.post_module.or(base.hooks.post_module),
+            },
+            options: ConfigOptions {
+                auto_backup: overlay.options.auto_backup,
+                dry_run: overlay.options.dry_run,
+                parallel_install: overlay.options.parallel_install,
+                custom: Self::merge_map(base.options.custom, overlay.options.custom),
+            },
+            security: SecuritySettings {
+                isolation_level: overlay
+                    .security
+                    .isolation_level
+                    .or(base.security.isolation_level),
+                network_isolation: overlay.security.network_isolation,
+                container_sandbox: overlay
+                    .security
+                    .container_sandbox
+                    .or(base.security.container_sandbox),
+                gpg_signing: overlay.security.gpg_signing,
+                vet_exemptions: Self::merge_dedup(
+                    base.security.vet_exemptions,
+                    overlay.security.vet_exemptions,
+                ),
+                required_vet_criteria: Self::merge_dedup(
+                    base.security.required_vet_criteria,
+                    overlay.security.required_vet_criteria,
+                ),
+                require_vetted_packages: overlay.security.require_vetted_packages,
+            },
+            aliases: Self::merge_map(base.aliases, overlay.aliases),
+            module_profiles: Self::merge_map(base.module_profiles, overlay.module_profiles),
+            // Keep the declaring file's own include list so `save` round
+            // trips it, rather than flattening it away into the merge.
+            include: overlay.include,
+        }
+    }
+
+    fn merge_dedup<T: PartialEq>(base: Vec<T>, overlay: Vec<T>) -> Vec<T> {
+        let mut merged = base;
+        for item in overlay {
+            if !merged.contains(&item) {
+                merged.push(item);
+            }
+        }
+        merged
+    }
+
+    fn merge_map<K: std::hash::Hash + Eq, V>(
+        base: HashMap<K, V>,
+        overlay: HashMap<K, V>,
+    ) -> HashMap<K, V> {
+        let mut merged = base;
+        for (key, value) in overlay {
+            merged.insert(key, value);
+        }
+        merged
     }
 
     /// Save configuration to YAML file
@@ -146,6 +349,9 @@ impl ConfigLoader {
             hooks: Hooks::default(),
             options: ConfigOptions::default(),
             security: SecuritySettings::default(),
+            aliases: HashMap::new(),
+            module_profiles: HashMap::new(),
+            include: None,
         }
     }
 }
@@ -174,4 +380,123 @@ mod tests {
         assert_eq!(config.name, loaded.name);
         assert_eq!(config.version, loaded.version);
     }
+
+    #[test]
+    fn test_alias_tokens_single_splits_on_whitespace() {
+        let alias = AliasTokens::Single("sync --dry-run".to_string());
+        assert_eq!(alias.tokens(), vec!["sync", "--dry-run"]);
+    }
+
+    #[test]
+    fn test_alias_tokens_list_passed_through() {
+        let alias = AliasTokens::List(vec!["sync".to_string(), "--dry-run".to_string()]);
+        assert_eq!(alias.tokens(), vec!["sync", "--dry-run"]);
+    }
+
+    #[test]
+    fn test_load_config_with_aliases() {
+        let mut config = ConfigLoader::new_default("test".to_string());
+        config.aliases.insert(
+            "up".to_string(),
+            AliasTokens::Single("sync --dry-run".to_string()),
+        );
+        let temp_file = NamedTempFile::new().unwrap();
+
+        ConfigLoader::save(&config, temp_file.path()).unwrap();
+        let loaded = ConfigLoader::load(temp_file.path()).unwrap();
+
+        assert_eq!(loaded.aliases.get("up").unwrap().tokens(), vec!["sync", "--dry-run"]);
+    }
+
+    #[test]
+    fn test_include_merges_base_with_overlay_winning() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::write(
+            dir.path().join("base.yml"),
+            "version: \"1.0\"\nname: base\nmodules:\n  - core/base-system\n  - core/bootloader\n",
+        )
+        .unwrap();
+
+        std::fs::write(
+            dir.path().join("laptop.yml"),
+            "version: \"1.0\"\nname: laptop\ninclude: base.yml\nmodules:\n  - core/bootloader\n  - desktop/hypr\n",
+        )
+        .unwrap();
+
+        let config = ConfigLoader::load(dir.path().join("laptop.yml")).unwrap();
+
+        assert_eq!(config.name, "laptop");
+        assert_eq!(
+            config.modules,
+            vec!["core/base-system", "core/bootloader", "desktop/hypr"]
+        );
+    }
+
+    #[test]
+    fn test_include_list_merges_depth_first_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::write(
+            dir.path().join("a.yml"),
+            "version: \"1.0\"\nname: a\nmodules:\n  - mod-a\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.yml"),
+            "version: \"1.0\"\nname: b\nmodules:\n  - mod-b\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("top.yml"),
+            "version: \"1.0\"\nname: top\ninclude:\n  - a.yml\n  - b.yml\nmodules:\n  - mod-top\n",
+        )
+        .unwrap();
+
+        let config = ConfigLoader::load(dir.path().join("top.yml")).unwrap();
+        assert_eq!(config.modules, vec!["mod-a", "mod-b", "mod-top"]);
+    }
+
+    #[test]
+    fn test_include_cycle_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        std::fs::write(
+            dir.path().join("a.yml"),
+            "version: \"1.0\"\nname: a\ninclude: b.yml\nmodules: []\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("b.yml"),
+            "version: \"1.0\"\nname: b\ninclude: a.yml\nmodules: []\n",
+        )
+        .unwrap();
+
+        let result = ConfigLoader::load(dir.path().join("a.yml"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_save_round_trips_include_without_flattening() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.yml"),
+            "version: \"1.0\"\nname: base\nmodules:\n  - core/base-system\n",
+        )
+        .unwrap();
+
+        let laptop_path = dir.path().join("laptop.yml");
+        std::fs::write(
+            &laptop_path,
+            "version: \"1.0\"\nname: laptop\ninclude: base.yml\nmodules: []\n",
+        )
+        .unwrap();
+
+        let config = ConfigLoader::load(&laptop_path).unwrap();
+        ConfigLoader::save(&config, &laptop_path).unwrap();
+
+        let saved = std::fs::read_to_string(&laptop_path).unwrap();
+        assert!(saved.contains("include: base.yml"));
+        assert!(!saved.contains("core/base-system"));
+    }
 }
@@ -48,6 +48,13 @@ pub enum S1bCr4ftError {
 
     #[error("Unknown error: {0}")]
     Unknown(String),
+
+    /// Wraps an earlier error with additional where-it-happened context,
+    /// added via the [`ErrorContext`] extension trait. Keeps the original
+    /// error as `source()` so [`S1bCr4ftError::report`] can still render the
+    /// full chain.
+    #[error("{0}")]
+    Context(String, #[source] Box<S1bCr4ftError>),
 }
 
 impl S1bCr4ftError {
@@ -75,6 +82,26 @@ impl S1bCr4ftError {
     pub fn gpg<S: Into<String>>(msg: S) -> Self {
         Self::Gpg(msg.into())
     }
+
+    /// Render the full cause chain, one line per error, e.g.:
+    ///
+    /// ```text
+    /// error: Failed to sync configuration
+    ///   caused by: IO error: No such file or directory (os error 2)
+    ///   caused by: No such file or directory (os error 2)
+    /// ```
+    ///
+    /// Mirrors anyhow's `Chain`/failure's `iter_causes`, since `to_string()`
+    /// alone only shows the outermost message.
+    pub fn report(&self) -> String {
+        let mut output = format!("error: {}", self);
+        let mut source = std::error::Error::source(self);
+        while let Some(err) = source {
+            output.push_str(&format!("\n  caused by: {}", err));
+            source = err.source();
+        }
+        output
+    }
 }
 
 // Missing audit error variant - need to add it to the enum
@@ -83,3 +110,44 @@ impl S1bCr4ftError {
         Self::Audit(msg.into())
     }
 }
+
+/// Ergonomic `.context(msg)` for `Result<T, S1bCr4ftError>`, wrapping a
+/// failure with additional context while preserving it as the source so the
+/// full chain is still available via [`S1bCr4ftError::report`].
+pub trait ErrorContext<T> {
+    fn context<S: Into<String>>(self, msg: S) -> Result<T>;
+}
+
+impl<T> ErrorContext<T> for Result<T> {
+    fn context<S: Into<String>>(self, msg: S) -> Result<T> {
+        self.map_err(|e| S1bCr4ftError::Context(msg.into(), Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_renders_full_chain() {
+        let io_err = S1bCr4ftError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "file not found",
+        ));
+        let wrapped = S1bCr4ftError::Context("Failed to load module".to_string(), Box::new(io_err));
+
+        let report = wrapped.report();
+        assert!(report.starts_with("error: Failed to load module"));
+        assert!(report.contains("caused by: IO error: file not found"));
+    }
+
+    #[test]
+    fn test_context_extension_preserves_source() {
+        let result: Result<()> = Err(S1bCr4ftError::config("bad version"));
+        let contextualized = result.context("Loading project config");
+
+        let error = contextualized.unwrap_err();
+        assert_eq!(error.to_string(), "Loading project config");
+        assert!(std::error::Error::source(&error).is_some());
+    }
+}
@@ -13,18 +13,29 @@
 pub mod config;
 pub mod module;
 pub mod package;
+pub mod cache;
+pub mod aur;
 pub mod backup;
 pub mod audit;
 pub mod hooks;
+pub mod lock;
+pub mod vet;
 pub mod validation;
+pub mod command_validator;
 pub mod error;
 
 pub use config::{Config, ConfigLoader};
-pub use module::{Module, ModuleResolver, ModuleRegistry};
-pub use package::{PackageManager, SyncOptions, SyncReport};
+pub use module::{
+    suggest_id, FixEntry, FixReport, Module, ModuleRegistry, ModuleResolver, ResolvedModule,
+};
+pub use package::{FailedPackage, PackageManager, SyncOptions, SyncReport};
+pub use package::vet::{PackageAudit, PackageAuditStore, PackageVetter};
+pub use cache::PackageCache;
 pub use backup::{BackupManager, BackupId};
 pub use audit::{AuditLogger, AuditEntry};
-pub use error::{Result, S1bCr4ftError};
+pub use lock::{LockFile, ModuleLockEntry};
+pub use vet::{AuditStore, ModuleAudit, ModuleVetter};
+pub use error::{ErrorContext, Result, S1bCr4ftError};
 
 /// S1bCr4ft version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,280 @@
+use crate::error::{Result, S1bCr4ftError};
+use crate::module::{ModuleRegistry, ResolvedModule};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// A single module's resolved contents, serialized in a canonical
+/// (sorted-key, sorted-list) form so the same module always hashes to the
+/// same integrity value regardless of map/iteration order.
+#[derive(Debug, Clone, Serialize)]
+struct ModuleManifest {
+    id: String,
+    version: String,
+    packages: Vec<String>,
+    aur_packages: Vec<String>,
+    files: BTreeMap<String, String>,
+}
+
+impl ModuleManifest {
+    fn integrity(&self) -> Result<String> {
+        let canonical = serde_json::to_string(self)?;
+        let mut hasher = Sha256::new();
+        hasher.update(canonical.as_bytes());
+        Ok(format!("sha256-{:x}", hasher.finalize()))
+    }
+}
+
+/// One module's lockfile entry: the version resolution picked and the
+/// integrity hash of its resolved package/file manifest at lock time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleLockEntry {
+    pub version: String,
+    pub integrity: String,
+}
+
+/// `config.lock`: one integrity entry per module, keyed by module ID,
+/// mirroring Deno's single-hash-per-package lockfile design rather than
+/// recording one entry per installed file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockFile {
+    pub version: String,
+    pub modules: BTreeMap<String, ModuleLockEntry>,
+}
+
+impl LockFile {
+    /// Derive the lockfile path for a given `config.yml` path, e.g.
+    /// `config.yml` -> `config.lock`.
+    pub fn path_for_config<P: AsRef<Path>>(config_path: P) -> PathBuf {
+        config_path.as_ref().with_extension("lock")
+    }
+
+    /// Generate a lockfile from a set of resolved modules, looking up each
+    /// module's full manifest (packages, AUR packages, bundled files) in
+    /// `registry`.
+    pub fn generate(registry: &ModuleRegistry, resolved: &[ResolvedModule]) -> Result<Self> {
+        let mut modules = BTreeMap::new();
+
+        for resolved_module in resolved {
+            let module = registry.get(&resolved_module.id).ok_or_else(|| {
+                S1bCr4ftError::config(format!(
+                    "Cannot lock module '{}': not found in registry",
+                    resolved_module.id
+                ))
+            })?;
+
+            let integrity = manifest_for(module.id.clone(), module.version.clone(), module)?
+                .integrity()?;
+
+            modules.insert(
+                resolved_module.id.clone(),
+                ModuleLockEntry {
+                    version: resolved_module.version.clone(),
+                    integrity,
+                },
+            );
+        }
+
+        Ok(Self {
+            version: "1".to_string(),
+            modules,
+        })
+    }
+
+    /// Recompute each resolved module's integrity hash and compare it
+    /// against this lockfile, returning an error naming the first module
+    /// whose resolved contents no longer match what was locked.
+    pub fn verify(&self, registry: &ModuleRegistry, resolved: &[ResolvedModule]) -> Result<()> {
+        for resolved_module in resolved {
+            let locked = self.modules.get(&resolved_module.id).ok_or_else(|| {
+                S1bCr4ftError::config(format!(
+                    "Module '{}' is not present in config.lock; run `lock` to regenerate",
+                    resolved_module.id
+                ))
+            })?;
+
+            let module = registry.get(&resolved_module.id).ok_or_else(|| {
+                S1bCr4ftError::config(format!(
+                    "Cannot verify module '{}': not found in registry",
+                    resolved_module.id
+                ))
+            })?;
+
+            let current =
+                manifest_for(module.id.clone(), module.version.clone(), module)?.integrity()?;
+
+            if current != locked.integrity {
+                return Err(S1bCr4ftError::config(format!(
+                    "Module '{}' has drifted from config.lock (expected {}, got {})",
+                    resolved_module.id, locked.integrity, current
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Load a lockfile from disk.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+            S1bCr4ftError::config(format!("Failed to read lockfile: {}", e))
+        })?;
+        let lock_file: LockFile = serde_json::from_str(&content)?;
+        Ok(lock_file)
+    }
+
+    /// Write this lockfile to disk.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path.as_ref(), content).map_err(|e| {
+            S1bCr4ftError::config(format!("Failed to write lockfile: {}", e))
+        })?;
+        Ok(())
+    }
+}
+
+fn manifest_for(
+    id: String,
+    version: String,
+    module: &crate::module::Module,
+) -> Result<ModuleManifest> {
+    let mut packages = module.packages.clone();
+    packages.sort();
+
+    let mut aur_packages = module.aur_packages.clone();
+    aur_packages.sort();
+
+    let files = module
+        .files
+        .iter()
+        .map(|(path, content)| (path.to_string_lossy().into_owned(), content.clone()))
+        .collect();
+
+    Ok(ModuleManifest {
+        id,
+        version,
+        packages,
+        aur_packages,
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::module::Module;
+    use std::collections::HashMap;
+    use tempfile::{NamedTempFile, TempDir};
+
+    fn sample_module(id: &str) -> Module {
+        Module {
+            id: id.to_string(),
+            name: "Sample".to_string(),
+            description: "A sample module".to_string(),
+            category: "core".to_string(),
+            version: "1.0.0".to_string(),
+            dependencies: Default::default(),
+            conflicts: Vec::new(),
+            packages: vec!["b-pkg".to_string(), "a-pkg".to_string()],
+            aur_packages: Vec::new(),
+            commands: Vec::new(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Writes each module out as a `module.yml` under a fresh temp directory
+    /// and loads it back through [`ModuleRegistry`], since its module map is
+    /// private and only populated via `load_all`.
+    fn registry_with(modules: Vec<Module>) -> (TempDir, ModuleRegistry) {
+        let dir = TempDir::new().unwrap();
+        for (i, module) in modules.iter().enumerate() {
+            let module_dir = dir.path().join(format!("module-{}", i));
+            std::fs::create_dir_all(&module_dir).unwrap();
+            let yaml = serde_yaml::to_string(module).unwrap();
+            std::fs::write(module_dir.join("module.yml"), yaml).unwrap();
+        }
+
+        let mut registry = ModuleRegistry::new(dir.path());
+        registry.load_all().unwrap();
+        (dir, registry)
+    }
+
+    #[test]
+    fn test_generate_is_stable_regardless_of_package_order() {
+        let mut shuffled = sample_module("core/base-system");
+        shuffled.packages = vec!["a-pkg".to_string(), "b-pkg".to_string()];
+
+        let (_dir_a, registry_a) = registry_with(vec![sample_module("core/base-system")]);
+        let (_dir_b, registry_b) = registry_with(vec![shuffled]);
+
+        let resolved = vec![ResolvedModule {
+            id: "core/base-system".to_string(),
+            version: "1.0.0".to_string(),
+        }];
+
+        let lock_a = LockFile::generate(&registry_a, &resolved).unwrap();
+        let lock_b = LockFile::generate(&registry_b, &resolved).unwrap();
+
+        assert_eq!(
+            lock_a.modules["core/base-system"].integrity,
+            lock_b.modules["core/base-system"].integrity
+        );
+    }
+
+    #[test]
+    fn test_verify_detects_drift() {
+        let (_dir, registry) = registry_with(vec![sample_module("core/base-system")]);
+        let resolved = vec![ResolvedModule {
+            id: "core/base-system".to_string(),
+            version: "1.0.0".to_string(),
+        }];
+
+        let lock_file = LockFile::generate(&registry, &resolved).unwrap();
+
+        let mut drifted_module = sample_module("core/base-system");
+        drifted_module.packages.push("extra-pkg".to_string());
+        let (_drifted_dir, drifted_registry) = registry_with(vec![drifted_module]);
+
+        let result = lock_file.verify(&drifted_registry, &resolved);
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("core/base-system"));
+    }
+
+    #[test]
+    fn test_verify_passes_when_unchanged() {
+        let (_dir, registry) = registry_with(vec![sample_module("core/base-system")]);
+        let resolved = vec![ResolvedModule {
+            id: "core/base-system".to_string(),
+            version: "1.0.0".to_string(),
+        }];
+
+        let lock_file = LockFile::generate(&registry, &resolved).unwrap();
+        assert!(lock_file.verify(&registry, &resolved).is_ok());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let (_dir, registry) = registry_with(vec![sample_module("core/base-system")]);
+        let resolved = vec![ResolvedModule {
+            id: "core/base-system".to_string(),
+            version: "1.0.0".to_string(),
+        }];
+        let lock_file = LockFile::generate(&registry, &resolved).unwrap();
+
+        let temp_file = NamedTempFile::new().unwrap();
+        lock_file.save(temp_file.path()).unwrap();
+        let loaded = LockFile::load(temp_file.path()).unwrap();
+
+        assert_eq!(
+            lock_file.modules["core/base-system"].integrity,
+            loaded.modules["core/base-system"].integrity
+        );
+    }
+
+    #[test]
+    fn test_path_for_config_replaces_extension() {
+        let path = LockFile::path_for_config("project/config.yml");
+        assert_eq!(path, PathBuf::from("project/config.lock"));
+    }
+}
@@ -1,8 +1,72 @@
+use crate::audit::{AuditAction, AuditLogger};
+use crate::backup::BackupManager;
+use crate::config::{Config, ConfigLoader};
 use crate::error::{Result, S1bCr4ftError};
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+/// Dependency list as written in `module.yml`.
+///
+/// Accepts two shapes so existing modules don't need to be rewritten:
+///
+/// ```yaml
+/// dependencies:
+///   - core/base-system          # any version
+/// ```
+///
+/// ```yaml
+/// dependencies:
+///   core/base-system: ">=2.0, <3.0"   # SemVer range
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DependencyMap {
+    Bare(Vec<String>),
+    Versioned(HashMap<String, String>),
+}
+
+impl Default for DependencyMap {
+    fn default() -> Self {
+        DependencyMap::Bare(Vec::new())
+    }
+}
+
+impl DependencyMap {
+    /// Dependency IDs only, ignoring any version requirement.
+    pub fn ids(&self) -> Vec<String> {
+        match self {
+            DependencyMap::Bare(ids) => ids.clone(),
+            DependencyMap::Versioned(map) => map.keys().cloned().collect(),
+        }
+    }
+
+    /// Dependency IDs paired with their parsed SemVer requirement. Bare
+    /// dependencies default to `*` (any version).
+    pub fn requirements(&self) -> Result<Vec<(String, VersionReq)>> {
+        match self {
+            DependencyMap::Bare(ids) => Ok(ids
+                .iter()
+                .map(|id| (id.clone(), VersionReq::STAR))
+                .collect()),
+            DependencyMap::Versioned(map) => map
+                .iter()
+                .map(|(id, req)| {
+                    VersionReq::parse(req)
+                        .map(|r| (id.clone(), r))
+                        .map_err(|e| {
+                            S1bCr4ftError::module(format!(
+                                "Invalid version requirement for dependency '{}': {}",
+                                id, e
+                            ))
+                        })
+                })
+                .collect(),
+        }
+    }
+}
+
 /// Module metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Module {
@@ -21,9 +85,9 @@ pub struct Module {
     /// Version
     pub version: String,
 
-    /// Dependencies (other module IDs)
+    /// Dependencies (other module IDs, optionally version-constrained)
     #[serde(default)]
-    pub dependencies: Vec<String>,
+    pub dependencies: DependencyMap,
 
     /// Conflicts with (module IDs)
     #[serde(default)]
@@ -45,9 +109,22 @@ pub struct Module {
     pub files: HashMap<PathBuf, String>,
 }
 
+impl Module {
+    /// Parsed SemVer for this module, if `version` is well-formed.
+    pub fn semver(&self) -> Result<Version> {
+        Version::parse(&self.version).map_err(|e| {
+            S1bCr4ftError::module(format!(
+                "Module '{}' has invalid version '{}': {}",
+                self.id, self.version, e
+            ))
+        })
+    }
+}
+
 /// Module registry
 pub struct ModuleRegistry {
-    modules: HashMap<String, Module>,
+    /// All known versions of each module, keyed by module ID
+    modules: HashMap<String, Vec<Module>>,
     module_dir: PathBuf,
 }
 
@@ -73,7 +150,10 @@ impl ModuleRegistry {
         {
             if entry.file_name() == "module.yml" {
                 let module = self.load_module(entry.path())?;
-                self.modules.insert(module.id.clone(), module);
+                self.modules
+                    .entry(module.id.clone())
+                    .or_default()
+                    .push(module);
             }
         }
 
@@ -89,97 +169,399 @@ impl ModuleRegistry {
         Ok(module)
     }
 
-    /// Get module by ID
+    /// Get the highest-versioned module registered under `id`
     pub fn get(&self, id: &str) -> Option<&Module> {
-        self.modules.get(id)
+        self.modules
+            .get(id)?
+            .iter()
+            .max_by(|a, b| match (a.semver(), b.semver()) {
+                (Ok(va), Ok(vb)) => va.cmp(&vb),
+                _ => std::cmp::Ordering::Equal,
+            })
+    }
+
+    /// Get every known version of a module, newest first
+    pub fn get_versions(&self, id: &str) -> Vec<&Module> {
+        let mut versions: Vec<&Module> = self
+            .modules
+            .get(id)
+            .map(|v| v.iter().collect())
+            .unwrap_or_default();
+        versions.sort_by(|a, b| match (a.semver(), b.semver()) {
+            (Ok(va), Ok(vb)) => vb.cmp(&va),
+            _ => std::cmp::Ordering::Equal,
+        });
+        versions
     }
 
-    /// List all modules
+    /// List all modules (all versions)
     pub fn list(&self) -> Vec<&Module> {
-        self.modules.values().collect()
+        self.modules.values().flatten().collect()
     }
 
-    /// Search modules by query
+    /// Search modules by query, falling back to a fuzzy (Levenshtein-based)
+    /// match on module ID when the substring search finds nothing, so a
+    /// typo'd query still surfaces the module the user probably meant.
     pub fn search(&self, query: &str) -> Vec<&Module> {
         let query_lower = query.to_lowercase();
-        self.modules
+        let substring_matches: Vec<&Module> = self
+            .modules
             .values()
+            .flatten()
             .filter(|m| {
                 m.name.to_lowercase().contains(&query_lower)
                     || m.description.to_lowercase().contains(&query_lower)
                     || m.id.to_lowercase().contains(&query_lower)
             })
-            .collect()
+            .collect();
+
+        if !substring_matches.is_empty() {
+            return substring_matches;
+        }
+
+        let mut fuzzy: Vec<(&Module, usize)> = self
+            .modules
+            .values()
+            .flatten()
+            .map(|m| (m, levenshtein_distance(&query_lower, &m.id.to_lowercase())))
+            .filter(|(_, dist)| *dist <= 3 || *dist <= query.chars().count() / 3)
+            .collect();
+        fuzzy.sort_by_key(|(_, dist)| *dist);
+
+        fuzzy.into_iter().map(|(m, _)| m).collect()
     }
+
+    /// Nearest known module ID to `query`, for "did you mean" hints when a
+    /// search/install query has no exact match.
+    pub fn suggest(&self, query: &str) -> Option<String> {
+        suggest_id(query, self.modules.keys().map(String::as_str))
+    }
+
+    /// A "Module not found" error for `id`, with a "did you mean" hint
+    /// appended when a close match exists.
+    fn not_found_error(&self, id: &str) -> S1bCr4ftError {
+        let hint = self
+            .suggest(id)
+            .map(|s| format!(" (did you mean '{}'?)", s))
+            .unwrap_or_default();
+        S1bCr4ftError::module(format!("Module not found: {}{}", id, hint))
+    }
+}
+
+/// Closest entry in `ids` to `query` by Levenshtein distance, if it's close
+/// enough to plausibly be a typo for it (cargo's "did you mean" heuristic):
+/// within an edit distance of 3, or within a third of the query's length.
+pub fn suggest_id<'a, I: IntoIterator<Item = &'a str>>(query: &str, ids: I) -> Option<String> {
+    let mut ids: Vec<&str> = ids.into_iter().collect();
+    ids.sort_unstable();
+
+    ids.into_iter()
+        .map(|id| (id, levenshtein_distance(query, id)))
+        .min_by_key(|(_, dist)| *dist)
+        .filter(|(_, dist)| *dist <= 3 || *dist <= query.chars().count() / 3)
+        .map(|(id, _)| id.to_string())
+}
+
+/// Edit distance between two strings (classic Wagner-Fischer DP).
+fn levenshtein_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut cur_row = vec![i + 1; b.len() + 1];
+
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur_row[j + 1] = (cur_row[j] + 1)
+                .min(prev_row[j + 1] + 1)
+                .min(prev_row[j] + cost);
+        }
+
+        prev_row = cur_row;
+    }
+
+    prev_row[b.len()]
+}
+
+/// Version picked for a single module during resolution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedModule {
+    pub id: String,
+    pub version: String,
+}
+
+/// Backtracking search state shared across the recursive resolution calls
+struct ResolutionState {
+    /// Module ID -> version selected so far
+    selected: HashMap<String, Version>,
+    /// Module IDs currently on the DFS stack (cycle detection)
+    visiting: HashSet<String>,
+    /// Resolved order, dependencies before dependents
+    order: Vec<String>,
+    /// Set by `require` when a later requirement conflicts with a module
+    /// that's already selected (and whose own `require` call frame has
+    /// already returned, so it can't retry a narrower candidate itself).
+    /// [`ModuleResolver::resolve_versions`] uses this to exclude that
+    /// version and retry the whole resolution from scratch.
+    conflict: Option<(String, Version)>,
 }
 
 /// Module dependency resolver
 pub struct ModuleResolver<'a> {
     registry: &'a ModuleRegistry,
+    /// Named bundles of module IDs (e.g. a `dev` profile), expanded in
+    /// place of the alias token wherever it appears in a module list passed
+    /// to [`Self::resolve`]/[`Self::resolve_versions`]/[`Self::check_conflicts`].
+    aliases: HashMap<String, Vec<String>>,
 }
 
 impl<'a> ModuleResolver<'a> {
     pub fn new(registry: &'a ModuleRegistry) -> Self {
-        Self { registry }
+        Self {
+            registry,
+            aliases: HashMap::new(),
+        }
     }
 
-    /// Resolve module dependencies in topological order
-    pub fn resolve(&self, module_ids: &[String]) -> Result<Vec<String>> {
-        let mut resolved = Vec::new();
-        let mut visited = HashSet::new();
-        let mut visiting = HashSet::new();
+    /// A resolver that also expands named module profiles/aliases (e.g.
+    /// `Config::module_profiles`) before resolution.
+    pub fn with_aliases(registry: &'a ModuleRegistry, aliases: HashMap<String, Vec<String>>) -> Self {
+        Self { registry, aliases }
+    }
 
+    /// Expand any of `module_ids` naming an alias into its member module
+    /// IDs, recursing so one alias can reference another (cargo-style
+    /// profile composition) while detecting cycles. Tokens that aren't a
+    /// known alias are passed through unchanged, so an unknown module ID
+    /// surfaces the usual "module not found" error later in resolution.
+    fn expand_aliases(&self, module_ids: &[String]) -> Result<Vec<String>> {
+        let mut expanded = Vec::new();
         for id in module_ids {
-            self.visit(id, &mut resolved, &mut visited, &mut visiting)?;
+            let mut visiting = HashSet::new();
+            self.expand_alias(id, &mut visiting, &mut expanded)?;
         }
-
-        Ok(resolved)
+        Ok(expanded)
     }
 
-    fn visit(
+    fn expand_alias(
         &self,
-        id: &str,
-        resolved: &mut Vec<String>,
-        visited: &mut HashSet<String>,
+        token: &str,
         visiting: &mut HashSet<String>,
+        out: &mut Vec<String>,
     ) -> Result<()> {
-        if visited.contains(id) {
-            return Ok(());
+        match self.aliases.get(token) {
+            Some(members) => {
+                if !visiting.insert(token.to_string()) {
+                    return Err(S1bCr4ftError::Dependency(format!(
+                        "Circular alias reference detected: {}",
+                        token
+                    )));
+                }
+                for member in members {
+                    self.expand_alias(member, visiting, out)?;
+                }
+                visiting.remove(token);
+                Ok(())
+            }
+            None => {
+                out.push(token.to_string());
+                Ok(())
+            }
+        }
+    }
+
+    /// Resolve module dependencies in topological order.
+    ///
+    /// Internally this selects a concrete version for every module in the
+    /// transitive closure (see [`ModuleResolver::resolve_versions`]); the
+    /// returned list only carries the module IDs for backwards compatibility.
+    pub fn resolve(&self, module_ids: &[String]) -> Result<Vec<String>> {
+        Ok(self
+            .resolve_versions(module_ids)?
+            .into_iter()
+            .map(|r| r.id)
+            .collect())
+    }
+
+    /// Resolve module dependencies to specific versions.
+    ///
+    /// Each (module, candidate-version) pick must satisfy every SemVer range
+    /// requested of it. Resolution greedily selects the newest compatible
+    /// version for each module and backtracks to the most recent decision
+    /// when a later constraint can't be met, trying the next-older candidate
+    /// instead. When a constraint conflicts with a module selected by an
+    /// *earlier, already-returned* top-level `require` call (so there's no
+    /// live call frame left to backtrack locally), the whole resolution is
+    /// retried with that version excluded, letting the earlier call fall
+    /// back to its own next-older candidate instead. An unsatisfiable set of
+    /// requirements yields a descriptive error naming the conflicting
+    /// constraint.
+    pub fn resolve_versions(&self, module_ids: &[String]) -> Result<Vec<ResolvedModule>> {
+        let module_ids = self.expand_aliases(module_ids)?;
+        let mut excluded: HashMap<String, HashSet<Version>> = HashMap::new();
+
+        loop {
+            let mut state = ResolutionState {
+                selected: HashMap::new(),
+                visiting: HashSet::new(),
+                order: Vec::new(),
+                conflict: None,
+            };
+
+            let mut failure = None;
+            for id in &module_ids {
+                if let Err(e) = self.require(id, &VersionReq::STAR, &mut state, &excluded) {
+                    failure = Some(e);
+                    break;
+                }
+            }
+
+            let err = match failure {
+                None => {
+                    return Ok(state
+                        .order
+                        .into_iter()
+                        .map(|id| ResolvedModule {
+                            version: state
+                                .selected
+                                .get(&id)
+                                .map(|v| v.to_string())
+                                .unwrap_or_default(),
+                            id,
+                        })
+                        .collect())
+                }
+                Some(e) => e,
+            };
+
+            // Only retry when the failure identifies a version to exclude,
+            // and excluding it is new progress (otherwise we'd loop forever
+            // re-hitting the same unsatisfiable conflict).
+            match state.conflict.take() {
+                Some((id, version)) if excluded.entry(id).or_default().insert(version) => continue,
+                _ => return Err(err),
+            }
         }
+    }
 
-        if visiting.contains(id) {
+    /// Ensure `id` is selected at a version satisfying `req`, recursing into
+    /// its dependencies and backtracking on conflict. `excluded` names
+    /// versions ruled out by an earlier attempt at the whole resolution (see
+    /// [`Self::resolve_versions`]).
+    fn require(
+        &self,
+        id: &str,
+        req: &VersionReq,
+        state: &mut ResolutionState,
+        excluded: &HashMap<String, HashSet<Version>>,
+    ) -> Result<()> {
+        // Cycle detection must run before the "already selected" shortcut
+        // below: a module mid-resolution is speculatively present in
+        // `selected`, so checking that first would mask the cycle.
+        if state.visiting.contains(id) {
             return Err(S1bCr4ftError::Dependency(format!(
                 "Circular dependency detected: {}",
                 id
             )));
         }
 
-        let module = self
-            .registry
-            .get(id)
-            .ok_or_else(|| S1bCr4ftError::module(format!("Module not found: {}", id)))?;
+        if let Some(existing) = state.selected.get(id).cloned() {
+            if req.matches(&existing) {
+                return Ok(());
+            }
+            state.conflict = Some((id.to_string(), existing.clone()));
+            return Err(S1bCr4ftError::Dependency(format!(
+                "Version conflict for '{}': already selected {} but another module requires {}",
+                id, existing, req
+            )));
+        }
 
-        visiting.insert(id.to_string());
+        let candidates = self.registry.get_versions(id);
+        if candidates.is_empty() {
+            return Err(self.registry.not_found_error(id));
+        }
+
+        let excluded_versions = excluded.get(id);
+        let mut matching: Vec<(&Module, Version)> = candidates
+            .into_iter()
+            .filter_map(|m| m.semver().ok().map(|v| (m, v)))
+            .filter(|(_, v)| req.matches(v))
+            .filter(|(_, v)| !excluded_versions.is_some_and(|vs| vs.contains(v)))
+            .collect();
+        matching.sort_by(|(_, a), (_, b)| b.cmp(a));
 
-        for dep in &module.dependencies {
-            self.visit(dep, resolved, visited, visiting)?;
+        if matching.is_empty() {
+            return Err(S1bCr4ftError::Dependency(format!(
+                "No version of '{}' satisfies requirement {}",
+                id, req
+            )));
         }
 
-        visiting.remove(id);
-        visited.insert(id.to_string());
-        resolved.push(id.to_string());
+        let mut last_err = None;
+
+        for (candidate, version) in matching {
+            let selected_snapshot = state.selected.clone();
+            let order_len = state.order.len();
+
+            state.visiting.insert(id.to_string());
+            state.selected.insert(id.to_string(), version.clone());
+
+            let attempt = self.try_candidate(candidate, state, excluded);
+
+            state.visiting.remove(id);
+
+            match attempt {
+                Ok(()) => {
+                    state.order.push(id.to_string());
+                    return Ok(());
+                }
+                Err(e) => {
+                    // Backtrack: undo this decision and try the next-older version
+                    state.selected = selected_snapshot;
+                    state.order.truncate(order_len);
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| {
+            S1bCr4ftError::Dependency(format!("Unable to resolve '{}' satisfying {}", id, req))
+        }))
+    }
+
+    fn try_candidate(
+        &self,
+        candidate: &Module,
+        state: &mut ResolutionState,
+        excluded: &HashMap<String, HashSet<Version>>,
+    ) -> Result<()> {
+        for conflict in &candidate.conflicts {
+            if state.selected.contains_key(conflict) {
+                return Err(S1bCr4ftError::Dependency(format!(
+                    "Conflict detected: {} conflicts with {}",
+                    candidate.id, conflict
+                )));
+            }
+        }
+
+        for (dep_id, dep_req) in candidate.dependencies.requirements()? {
+            self.require(&dep_id, &dep_req, state, excluded)?;
+        }
 
         Ok(())
     }
 
-    /// Check for conflicts
+    /// Check for conflicts among an already-chosen set of modules
     pub fn check_conflicts(&self, module_ids: &[String]) -> Result<()> {
+        let module_ids = self.expand_aliases(module_ids)?;
         let modules: Vec<_> = module_ids
             .iter()
             .map(|id| {
                 self.registry
                     .get(id)
-                    .ok_or_else(|| S1bCr4ftError::module(format!("Module not found: {}", id)))
+                    .ok_or_else(|| self.registry.not_found_error(id))
             })
             .collect::<Result<_>>()?;
 
@@ -196,6 +578,199 @@ impl<'a> ModuleResolver<'a> {
 
         Ok(())
     }
+
+    /// Diagnose `config.modules` the same way [`Self::resolve`] and
+    /// [`Self::check_conflicts`] would, and mechanically repair what's safe
+    /// to repair: drop references to modules that don't exist, de-duplicate
+    /// the list, reorder it to satisfy dependencies, and drop one side of a
+    /// declared conflict when only one side is actually depended on by
+    /// something else still in the list.
+    ///
+    /// Like [`crate::package::SyncOptions::dry_run`], `dry_run` previews the
+    /// fixes without changing anything; otherwise a backup of `config_path`
+    /// is taken via [`BackupManager::create_backup`] before it's rewritten,
+    /// and the applied fixes are recorded with
+    /// `AuditLogger::log(AuditAction::ConfigChange, ...)`.
+    pub fn diagnose_and_fix(
+        &self,
+        config: &mut Config,
+        config_path: &Path,
+        dry_run: bool,
+    ) -> Result<FixReport> {
+        let mut fixes = Vec::new();
+        let mut unresolved = Vec::new();
+        let mut modules = config.modules.clone();
+
+        // Drop references to modules (and alias tokens) that don't exist.
+        let mut dropped = Vec::new();
+        modules.retain(|id| {
+            if self.aliases.contains_key(id) || self.registry.get(id).is_some() {
+                true
+            } else {
+                dropped.push(id.clone());
+                false
+            }
+        });
+        for id in &dropped {
+            fixes.push(FixEntry {
+                description: format!("Removed '{}' from modules", id),
+                reason: "no module or alias with that ID exists".to_string(),
+            });
+        }
+
+        // De-duplicate, preserving first-seen order.
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        modules.retain(|id| {
+            if seen.insert(id.clone()) {
+                true
+            } else {
+                duplicates.push(id.clone());
+                false
+            }
+        });
+        for id in &duplicates {
+            fixes.push(FixEntry {
+                description: format!("Removed duplicate entry for '{}'", id),
+                reason: "already listed earlier in modules".to_string(),
+            });
+        }
+
+        // Drop one side of a declared conflict when only one side is
+        // actually depended on by something else still in the list; leave
+        // both in place (and record as unresolved) when that's ambiguous.
+        // This runs before the dependency reorder below, since `resolve`
+        // would itself fail on a still-conflicting pair.
+        let mut i = 0;
+        let mut reported_conflicts = HashSet::new();
+        while i < modules.len() {
+            let id = modules[i].clone();
+            let conflict = self
+                .registry
+                .get(&id)
+                .map(|m| m.conflicts.clone())
+                .unwrap_or_default()
+                .into_iter()
+                .find(|c| modules.contains(c));
+
+            let Some(conflict_id) = conflict else {
+                i += 1;
+                continue;
+            };
+
+            let id_is_needed = self.is_depended_on(&id, &modules);
+            let conflict_is_needed = self.is_depended_on(&conflict_id, &modules);
+
+            let to_remove = match (id_is_needed, conflict_is_needed) {
+                (true, false) => Some(conflict_id.clone()),
+                (false, true) => Some(id.clone()),
+                _ => None,
+            };
+
+            match to_remove {
+                Some(remove_id) => {
+                    let kept = if remove_id == id { &conflict_id } else { &id };
+                    modules.retain(|m| m != &remove_id);
+                    fixes.push(FixEntry {
+                        description: format!("Removed '{}' from modules", remove_id),
+                        reason: format!(
+                            "conflicts with '{}', which other selected modules depend on",
+                            kept
+                        ),
+                    });
+                    // Don't advance `i`: the list shrank, re-check this index.
+                }
+                None => {
+                    let pair = if id < conflict_id {
+                        (id.clone(), conflict_id.clone())
+                    } else {
+                        (conflict_id.clone(), id.clone())
+                    };
+                    if reported_conflicts.insert(pair) {
+                        unresolved.push(format!(
+                            "'{}' conflicts with '{}' and neither is clearly unused; resolve manually",
+                            id, conflict_id
+                        ));
+                    }
+                    i += 1;
+                }
+            }
+        }
+
+        // Reorder to satisfy dependencies. Skipped when an alias token is
+        // still present: ordering is only meaningful over concrete module
+        // IDs, and expanding aliases here would silently flatten them as a
+        // side effect of an unrelated fix. Also skipped when a conflict was
+        // left unresolved above, since `resolve` would only fail on that
+        // same pair again.
+        if unresolved.is_empty() && modules.iter().all(|id| !self.aliases.contains_key(id)) {
+            match self.resolve(&modules) {
+                Ok(resolved) if resolved != modules => {
+                    fixes.push(FixEntry {
+                        description: "Reordered modules to satisfy dependencies".to_string(),
+                        reason: "a dependency was listed after its dependent".to_string(),
+                    });
+                    modules = resolved;
+                }
+                Ok(_) => {}
+                Err(e) => unresolved.push(e.to_string()),
+            }
+        }
+
+        let changed = modules != config.modules;
+
+        if changed && !dry_run {
+            BackupManager::new()?.create_backup(
+                config_path,
+                Some("Pre-doctor auto-fix snapshot".to_string()),
+            )?;
+
+            config.modules = modules;
+            ConfigLoader::save(config, config_path)?;
+
+            AuditLogger::new()?.log(
+                AuditAction::ConfigChange,
+                serde_json::json!({ "fixes": fixes }),
+                true,
+            )?;
+        }
+
+        Ok(FixReport {
+            fixes,
+            unresolved,
+            dry_run,
+        })
+    }
+
+    /// Whether any module in `within` other than `id` itself depends on `id`.
+    fn is_depended_on(&self, id: &str, within: &[String]) -> bool {
+        within.iter().any(|other| {
+            other != id
+                && self
+                    .registry
+                    .get(other)
+                    .map(|m| m.dependencies.ids().iter().any(|d| d == id))
+                    .unwrap_or(false)
+        })
+    }
+}
+
+/// A single mechanically-applied (or, in a dry run, proposed) remediation
+/// produced by [`ModuleResolver::diagnose_and_fix`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixEntry {
+    pub description: String,
+    pub reason: String,
+}
+
+/// Result of [`ModuleResolver::diagnose_and_fix`]: every fix applied (or, in
+/// a dry run, that would be applied), plus problems it found but couldn't
+/// resolve on its own.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixReport {
+    pub fixes: Vec<FixEntry>,
+    pub unresolved: Vec<String>,
+    pub dry_run: bool,
 }
 
 #[cfg(test)]
@@ -207,4 +782,250 @@ mod tests {
         let registry = ModuleRegistry::new("/tmp/modules");
         assert_eq!(registry.list().len(), 0);
     }
+
+    #[test]
+    fn test_suggest_id_finds_close_typo() {
+        let ids = ["red-team/c2-frameworks/sliver-c2", "core/base-system"];
+        assert_eq!(
+            suggest_id("red-team/c2-framworks/sliver-c2", ids),
+            Some("red-team/c2-frameworks/sliver-c2".to_string())
+        );
+    }
+
+    #[test]
+    fn test_suggest_id_rejects_distant_candidates() {
+        let ids = ["core/base-system"];
+        assert_eq!(suggest_id("red-team/c2-frameworks/sliver-c2", ids), None);
+    }
+
+    #[test]
+    fn test_levenshtein_distance() {
+        assert_eq!(levenshtein_distance("kitten", "sitting"), 3);
+        assert_eq!(levenshtein_distance("same", "same"), 0);
+    }
+
+    fn registry_with_base_system() -> (tempfile::TempDir, ModuleRegistry) {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("module.yml"),
+            "id: core/base-system\nname: Base System\ndescription: Essentials\n\
+             category: core\nversion: 1.0.0\npackages: []\n",
+        )
+        .unwrap();
+
+        let mut registry = ModuleRegistry::new(dir.path());
+        registry.load_all().unwrap();
+        (dir, registry)
+    }
+
+    fn registry_with_modules(yamls: &[&str]) -> (tempfile::TempDir, ModuleRegistry) {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (i, yaml) in yamls.iter().enumerate() {
+            let sub = dir.path().join(format!("m{}", i));
+            std::fs::create_dir_all(&sub).unwrap();
+            std::fs::write(sub.join("module.yml"), yaml).unwrap();
+        }
+
+        let mut registry = ModuleRegistry::new(dir.path());
+        registry.load_all().unwrap();
+        (dir, registry)
+    }
+
+    fn test_config(modules: Vec<&str>) -> Config {
+        Config {
+            version: "1.0".to_string(),
+            name: "test".to_string(),
+            description: String::new(),
+            modules: modules.into_iter().map(|m| m.to_string()).collect(),
+            dotfiles: Vec::new(),
+            hooks: Default::default(),
+            options: Default::default(),
+            security: Default::default(),
+            aliases: Default::default(),
+            module_profiles: Default::default(),
+            include: None,
+        }
+    }
+
+    #[test]
+    fn test_search_falls_back_to_fuzzy_match_on_no_substring_hit() {
+        let (_dir, registry) = registry_with_base_system();
+
+        let results = registry.search("core/base-systym");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "core/base-system");
+    }
+
+    #[test]
+    fn test_resolver_module_not_found_includes_suggestion() {
+        let (_dir, registry) = registry_with_base_system();
+        let resolver = ModuleResolver::new(&registry);
+
+        let err = resolver
+            .resolve(&["core/base-systym".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("did you mean 'core/base-system'?"));
+    }
+
+    #[test]
+    fn test_check_conflicts_module_not_found_includes_suggestion() {
+        let (_dir, registry) = registry_with_base_system();
+        let resolver = ModuleResolver::new(&registry);
+
+        let err = resolver
+            .check_conflicts(&["core/base-systym".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("did you mean 'core/base-system'?"));
+    }
+
+    #[test]
+    fn test_resolve_expands_alias_into_member_modules() {
+        let (_dir, registry) = registry_with_base_system();
+        let aliases = HashMap::from([(
+            "base".to_string(),
+            vec!["core/base-system".to_string()],
+        )]);
+        let resolver = ModuleResolver::with_aliases(&registry, aliases);
+
+        let resolved = resolver.resolve(&["base".to_string()]).unwrap();
+        assert_eq!(resolved, vec!["core/base-system".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_expands_one_level_of_alias_to_alias_reference() {
+        let (_dir, registry) = registry_with_base_system();
+        let aliases = HashMap::from([
+            ("base".to_string(), vec!["core/base-system".to_string()]),
+            ("everything".to_string(), vec!["base".to_string()]),
+        ]);
+        let resolver = ModuleResolver::with_aliases(&registry, aliases);
+
+        let resolved = resolver.resolve(&["everything".to_string()]).unwrap();
+        assert_eq!(resolved, vec!["core/base-system".to_string()]);
+    }
+
+    #[test]
+    fn test_resolve_detects_alias_cycle() {
+        let (_dir, registry) = registry_with_base_system();
+        let aliases = HashMap::from([
+            ("a".to_string(), vec!["b".to_string()]),
+            ("b".to_string(), vec!["a".to_string()]),
+        ]);
+        let resolver = ModuleResolver::with_aliases(&registry, aliases);
+
+        let err = resolver.resolve(&["a".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("Circular alias reference"));
+    }
+
+    #[test]
+    fn test_resolve_unknown_alias_or_module_uses_not_found_path() {
+        let (_dir, registry) = registry_with_base_system();
+        let resolver = ModuleResolver::with_aliases(&registry, HashMap::new());
+
+        let err = resolver.resolve(&["nonexistent-alias".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("Module not found"));
+    }
+
+    #[test]
+    fn test_diagnose_and_fix_drops_nonexistent_module() {
+        let (_dir, registry) = registry_with_base_system();
+        let resolver = ModuleResolver::new(&registry);
+        let mut config = test_config(vec!["core/base-system", "does/not-exist"]);
+        let config_file = tempfile::NamedTempFile::new().unwrap();
+
+        let report = resolver
+            .diagnose_and_fix(&mut config, config_file.path(), true)
+            .unwrap();
+
+        assert!(report.dry_run);
+        assert_eq!(report.fixes.len(), 1);
+        assert!(report.fixes[0].description.contains("does/not-exist"));
+        // A dry run only previews fixes; the config itself is untouched.
+        assert_eq!(
+            config.modules,
+            vec!["core/base-system".to_string(), "does/not-exist".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_diagnose_and_fix_dedupes_modules() {
+        let (_dir, registry) = registry_with_base_system();
+        let resolver = ModuleResolver::new(&registry);
+        let mut config = test_config(vec!["core/base-system", "core/base-system"]);
+        let config_file = tempfile::NamedTempFile::new().unwrap();
+
+        let report = resolver
+            .diagnose_and_fix(&mut config, config_file.path(), true)
+            .unwrap();
+
+        assert_eq!(report.fixes.len(), 1);
+        assert!(report.fixes[0].description.contains("duplicate"));
+    }
+
+    #[test]
+    fn test_diagnose_and_fix_reorders_for_dependencies() {
+        let (_dir, registry) = registry_with_modules(&[
+            "id: dep/lib\nname: Lib\ndescription: d\ncategory: core\nversion: 1.0.0\npackages: []\n",
+            "id: dep/app\nname: App\ndescription: d\ncategory: core\nversion: 1.0.0\n\
+             packages: []\ndependencies:\n  - dep/lib\n",
+        ]);
+        let resolver = ModuleResolver::new(&registry);
+        let mut config = test_config(vec!["dep/app", "dep/lib"]);
+        let config_file = tempfile::NamedTempFile::new().unwrap();
+
+        let report = resolver
+            .diagnose_and_fix(&mut config, config_file.path(), true)
+            .unwrap();
+
+        assert!(report
+            .fixes
+            .iter()
+            .any(|f| f.description.contains("Reordered")));
+    }
+
+    #[test]
+    fn test_diagnose_and_fix_removes_unused_side_of_conflict() {
+        let (_dir, registry) = registry_with_modules(&[
+            "id: pick/x\nname: X\ndescription: d\ncategory: core\nversion: 1.0.0\n\
+             packages: []\nconflicts:\n  - pick/y\n",
+            "id: pick/y\nname: Y\ndescription: d\ncategory: core\nversion: 1.0.0\n\
+             packages: []\nconflicts:\n  - pick/x\n",
+            "id: pick/z\nname: Z\ndescription: d\ncategory: core\nversion: 1.0.0\n\
+             packages: []\ndependencies:\n  - pick/x\n",
+        ]);
+        let resolver = ModuleResolver::new(&registry);
+        let mut config = test_config(vec!["pick/x", "pick/y", "pick/z"]);
+        let config_file = tempfile::NamedTempFile::new().unwrap();
+
+        let report = resolver
+            .diagnose_and_fix(&mut config, config_file.path(), true)
+            .unwrap();
+
+        assert!(report
+            .fixes
+            .iter()
+            .any(|f| f.description.contains("pick/y")));
+        assert!(report.unresolved.is_empty());
+    }
+
+    #[test]
+    fn test_diagnose_and_fix_leaves_ambiguous_conflict_unresolved() {
+        let (_dir, registry) = registry_with_modules(&[
+            "id: amb/x\nname: X\ndescription: d\ncategory: core\nversion: 1.0.0\n\
+             packages: []\nconflicts:\n  - amb/y\n",
+            "id: amb/y\nname: Y\ndescription: d\ncategory: core\nversion: 1.0.0\n\
+             packages: []\nconflicts:\n  - amb/x\n",
+        ]);
+        let resolver = ModuleResolver::new(&registry);
+        let mut config = test_config(vec!["amb/x", "amb/y"]);
+        let config_file = tempfile::NamedTempFile::new().unwrap();
+
+        let report = resolver
+            .diagnose_and_fix(&mut config, config_file.path(), true)
+            .unwrap();
+
+        assert!(report.fixes.is_empty());
+        assert_eq!(report.unresolved.len(), 1);
+        assert!(report.unresolved[0].contains("amb/x"));
+    }
 }
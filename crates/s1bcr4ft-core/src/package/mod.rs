@@ -0,0 +1,681 @@
+pub mod vet;
+
+use crate::cache::PackageCache;
+use crate::command_validator::{self, CommandValidator};
+use crate::error::{Result, S1bCr4ftError};
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use vet::{PackageAuditStore, PackageVetter};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncOptions {
+    pub dry_run: bool,
+    pub force: bool,
+    /// Unused for repo/AUR-helper installs: `pacman` (and `paru`/`yay`,
+    /// which wrap it) take an exclusive transaction lock and refuse a
+    /// second concurrent invocation outright instead of queuing, so those
+    /// always run as a single batched invocation regardless of this flag.
+    /// Kept for callers that set it and for forwards compatibility with a
+    /// genuinely parallelizable install path (e.g. native AUR builds).
+    pub parallel: bool,
+
+    /// See `parallel` - not read by the current repo/AUR-helper install
+    /// path for the same reason.
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+
+    /// How often, in seconds, to refresh the cached `sudo` timestamp while a
+    /// non-dry-run sync is in flight. See [`SudoLoop`].
+    #[serde(default = "default_sudo_refresh_secs")]
+    pub sudo_refresh_secs: u64,
+
+    /// Reject packages that aren't vetted: audited for
+    /// `required_vet_criteria` (or exempted) in the [`vet::PackageAuditStore`],
+    /// failing them into `SyncReport::packages_failed` rather than aborting
+    /// the whole sync. AUR packages always additionally require an
+    /// `aur-trusted` audit on top of `required_vet_criteria`, since they're
+    /// unreviewed upstream.
+    #[serde(default)]
+    pub require_vetted: bool,
+
+    /// Criteria a package must satisfy when `require_vetted` is set.
+    #[serde(default = "default_vet_criteria")]
+    pub required_vet_criteria: Vec<String>,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            force: false,
+            parallel: true,
+            max_concurrency: default_max_concurrency(),
+            sudo_refresh_secs: default_sudo_refresh_secs(),
+            require_vetted: false,
+            required_vet_criteria: default_vet_criteria(),
+        }
+    }
+}
+
+fn default_vet_criteria() -> Vec<String> {
+    vec!["reviewed".to_string()]
+}
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+fn default_sudo_refresh_secs() -> u64 {
+    60
+}
+
+/// Keeps the cached `sudo` timestamp alive for the duration of a long-running
+/// privileged operation. `start` validates privileges once with `sudo -v`
+/// (returning an error immediately if that fails, rather than letting the
+/// first package install hit a surprise password prompt), then spawns a
+/// background thread that runs `sudo -n -v` on `interval` until the loop is
+/// dropped or [`Self::stop`] is called.
+pub struct SudoLoop {
+    keep_running: Arc<AtomicBool>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl SudoLoop {
+    pub fn start(interval: Duration) -> Result<Self> {
+        let status = std::process::Command::new("sudo")
+            .arg("-v")
+            .status()
+            .map_err(|e| S1bCr4ftError::package(format!("Failed to run sudo: {}", e)))?;
+
+        if !status.success() {
+            return Err(S1bCr4ftError::package(
+                "sudo privilege check failed; re-run with a user that can sudo",
+            ));
+        }
+
+        let keep_running = Arc::new(AtomicBool::new(true));
+        let keep_running_bg = keep_running.clone();
+
+        let handle = std::thread::spawn(move || {
+            while keep_running_bg.load(Ordering::Relaxed) {
+                std::thread::sleep(interval);
+                if !keep_running_bg.load(Ordering::Relaxed) {
+                    break;
+                }
+                let _ = std::process::Command::new("sudo").args(["-n", "-v"]).status();
+            }
+        });
+
+        Ok(Self {
+            keep_running,
+            handle: Some(handle),
+        })
+    }
+
+    /// Stop the background refresh thread and wait for it to exit.
+    pub fn stop(mut self) {
+        self.stop_inner();
+    }
+
+    fn stop_inner(&mut self) {
+        self.keep_running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for SudoLoop {
+    fn drop(&mut self) {
+        self.stop_inner();
+    }
+}
+
+/// A package that didn't make it into [`SyncReport::packages_installed`],
+/// with a human-readable reason (a failed install command, or missing vet
+/// criteria) so callers can surface more than just the bare name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedPackage {
+    pub package: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub packages_installed: Vec<String>,
+    pub packages_failed: Vec<FailedPackage>,
+    pub commands_executed: Vec<String>,
+    pub duration_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PackageHelper {
+    Pacman,
+    Paru,
+    Yay,
+    /// No AUR helper installed, but `git` and `makepkg` are: build AUR
+    /// packages natively (see `crate::aur`) instead of hard-erroring.
+    Makepkg,
+}
+
+impl PackageHelper {
+    /// Detect which package helper is available
+    pub fn detect() -> Self {
+        if std::process::Command::new("paru")
+            .arg("--version")
+            .output()
+            .is_ok()
+        {
+            PackageHelper::Paru
+        } else if std::process::Command::new("yay")
+            .arg("--version")
+            .output()
+            .is_ok()
+        {
+            PackageHelper::Yay
+        } else if std::process::Command::new("makepkg")
+            .arg("--version")
+            .output()
+            .is_ok()
+            && std::process::Command::new("git")
+                .arg("--version")
+                .output()
+                .is_ok()
+        {
+            PackageHelper::Makepkg
+        } else {
+            PackageHelper::Pacman
+        }
+    }
+
+    pub fn command(&self) -> &str {
+        match self {
+            PackageHelper::Pacman => "pacman",
+            PackageHelper::Paru => "paru",
+            PackageHelper::Yay => "yay",
+            PackageHelper::Makepkg => "makepkg",
+        }
+    }
+
+    pub fn can_install_aur(&self) -> bool {
+        matches!(
+            self,
+            PackageHelper::Paru | PackageHelper::Yay | PackageHelper::Makepkg
+        )
+    }
+}
+
+pub struct PackageManager {
+    helper: PackageHelper,
+}
+
+impl PackageManager {
+    pub fn new() -> Self {
+        Self {
+            helper: PackageHelper::detect(),
+        }
+    }
+
+    pub fn with_helper(helper: PackageHelper) -> Self {
+        Self { helper }
+    }
+
+    /// Install official repository packages with a single batched
+    /// invocation (`pacman -S pkg1 pkg2 ... --noconfirm`), never one
+    /// process per package - `pacman` holds an exclusive transaction lock,
+    /// so a second concurrent invocation would fail outright rather than
+    /// queue behind the first.
+    pub async fn install_packages(
+        &self,
+        packages: &[String],
+        options: &SyncOptions,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        if packages.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut args = vec!["-S".to_string(), "--noconfirm".to_string()];
+        if !options.force {
+            args.push("--needed".to_string());
+        }
+
+        if options.dry_run {
+            log::info!("DRY RUN: Would install packages: {:?}", packages);
+            return Ok((packages.to_vec(), Vec::new()));
+        }
+
+        log::info!(
+            "Installing {} packages with {}",
+            packages.len(),
+            self.helper.command()
+        );
+
+        Ok(self.run_install_batch(packages, args).await)
+    }
+
+    /// Install AUR packages, batched the same way as [`Self::install_packages`]
+    /// (`paru`/`yay` wrap `pacman` and share its single transaction lock).
+    pub async fn install_aur_packages(
+        &self,
+        packages: &[String],
+        options: &SyncOptions,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        if packages.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        if !self.helper.can_install_aur() {
+            return Err(S1bCr4ftError::package(
+                "AUR packages require paru, yay, or makepkg + git. Please install one of them first.",
+            ));
+        }
+
+        if matches!(self.helper, PackageHelper::Makepkg) {
+            return Self::install_aur_packages_native(packages, options).await;
+        }
+
+        if options.dry_run {
+            log::info!("DRY RUN: Would install AUR packages: {:?}", packages);
+            return Ok((packages.to_vec(), Vec::new()));
+        }
+
+        log::info!(
+            "Installing {} AUR packages with {}",
+            packages.len(),
+            self.helper.command()
+        );
+
+        let args = vec![
+            "-S".to_string(),
+            "--noconfirm".to_string(),
+            "--needed".to_string(),
+        ];
+
+        Ok(self.run_install_batch(packages, args).await)
+    }
+
+    /// Native AUR fallback: resolve the build order via the AUR RPC, clone
+    /// each package's git repo, and build it with `makepkg -si`. In a dry
+    /// run this only resolves and logs the build order.
+    async fn install_aur_packages_native(
+        packages: &[String],
+        options: &SyncOptions,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let plan = crate::aur::resolve_build_order(
+            packages,
+            &crate::aur::fetch_info,
+            &|name| Self::is_repo_package(name),
+        )?;
+
+        if options.dry_run {
+            log::info!("DRY RUN: Resolved AUR build order: {:?}", plan.aur_order);
+            if !plan.repo_deps.is_empty() {
+                log::info!(
+                    "DRY RUN: Would install repo dependencies first: {:?}",
+                    plan.repo_deps
+                );
+            }
+            return Ok((packages.to_vec(), Vec::new()));
+        }
+
+        if !plan.repo_deps.is_empty() {
+            let repo_manager = Self::with_helper(PackageHelper::Pacman);
+            repo_manager
+                .install_packages(&plan.repo_deps, options)
+                .await?;
+        }
+
+        let mut installed = Vec::new();
+        let mut failed = Vec::new();
+
+        for package in &plan.aur_order {
+            let package_owned = package.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let dir = crate::aur::clone_package(&package_owned)?;
+                crate::aur::build_package(&dir)
+            })
+            .await
+            .map_err(|e| S1bCr4ftError::package(format!("AUR build task panicked: {}", e)))?;
+
+            match result {
+                Ok(()) => installed.push(package.clone()),
+                Err(e) => {
+                    log::error!("Failed to build AUR package {}: {}", package, e);
+                    failed.push(package.clone());
+                }
+            }
+        }
+
+        // Only report the packages actually requested, not transitive AUR
+        // build dependencies that were also built along the way.
+        let requested: std::collections::HashSet<&String> = packages.iter().collect();
+        installed.retain(|p| requested.contains(p));
+        failed.retain(|p| requested.contains(p));
+
+        Ok((installed, failed))
+    }
+
+    /// Whether `name` is available in the configured repos (and therefore
+    /// installable with `pacman -S` rather than needing an AUR build).
+    fn is_repo_package(name: &str) -> bool {
+        std::process::Command::new("pacman")
+            .args(["-Si", name])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Install packages in dependency order: stage `N` only begins once
+    /// every package in stage `N - 1` has resolved, the async analogue of
+    /// the order [`crate::module::ModuleResolver::resolve`] produces for
+    /// modules. Each stage is handed to [`Self::install_packages`] as one
+    /// batched invocation; its packages are not installed as separate
+    /// concurrent processes.
+    pub async fn install_staged(
+        &self,
+        stages: &[Vec<String>],
+        options: &SyncOptions,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let mut installed = Vec::new();
+        let mut failed = Vec::new();
+
+        for stage in stages {
+            let (stage_installed, stage_failed) = self.install_packages(stage, options).await?;
+            installed.extend(stage_installed);
+            failed.extend(stage_failed);
+        }
+
+        Ok((installed, failed))
+    }
+
+    /// Run a single `<helper> <args> pkg1 pkg2 ...` invocation covering all
+    /// of `packages` at once, since the helper's own transaction lock makes
+    /// installing them as separate concurrent processes unsafe. `pacman -S`
+    /// is all-or-nothing, so on failure every package in the batch is
+    /// reported failed, not just the one that actually conflicted.
+    async fn run_install_batch(
+        &self,
+        packages: &[String],
+        args: Vec<String>,
+    ) -> (Vec<String>, Vec<String>) {
+        let helper_cmd = self.helper.command().to_string();
+
+        let result = Command::new(&helper_cmd)
+            .args(&args)
+            .args(packages)
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .output()
+            .await;
+
+        match result {
+            Ok(output) if output.status.success() => (packages.to_vec(), Vec::new()),
+            Ok(_) => {
+                log::error!("Failed to install packages: {:?}", packages);
+                (Vec::new(), packages.to_vec())
+            }
+            Err(e) => {
+                log::error!("Failed to execute {}: {}", helper_cmd, e);
+                (Vec::new(), packages.to_vec())
+            }
+        }
+    }
+
+    /// Execute system commands
+    ///
+    /// Each command is parsed and checked against [`CommandValidator`]'s
+    /// whitelist (gated by the host's detected `init`/libc) before it runs,
+    /// so a module can't smuggle an arbitrary shell invocation into `sync`.
+    pub async fn execute_commands(&self, commands: &[String], dry_run: bool) -> Result<Vec<String>> {
+        let mut executed = Vec::new();
+        let validator =
+            CommandValidator::new().with_platform_config(command_validator::detect_platform_config());
+
+        for command in commands {
+            let parsed = validator.parse_and_validate(command)?;
+
+            if dry_run {
+                log::info!("DRY RUN: Would execute: {}", command);
+                executed.push(command.clone());
+                continue;
+            }
+
+            log::info!("Executing: {}", command);
+
+            let output = Command::new(&parsed.executable)
+                .args(&parsed.arguments)
+                .stdout(Stdio::inherit())
+                .stderr(Stdio::inherit())
+                .output()
+                .await
+                .map_err(|e| S1bCr4ftError::package(format!("Failed to execute command: {}", e)))?;
+
+            if output.status.success() {
+                executed.push(command.clone());
+            } else {
+                log::error!("Command failed: {}", command);
+            }
+        }
+
+        Ok(executed)
+    }
+
+    /// Full sync operation. Repo and AUR installs run concurrently with
+    /// each other; command hooks run after both finish.
+    pub async fn sync(
+        &self,
+        packages: &[String],
+        aur_packages: &[String],
+        commands: &[String],
+        options: &SyncOptions,
+    ) -> Result<SyncReport> {
+        let start = Instant::now();
+
+        let _sudo_loop = if options.dry_run {
+            None
+        } else {
+            Some(SudoLoop::start(Duration::from_secs(
+                options.sudo_refresh_secs,
+            ))?)
+        };
+
+        // Skip packages the local cache already knows are installed, so the
+        // sync planner doesn't hand already-satisfied packages to the
+        // helper. An empty or stale cache just means nothing is skipped.
+        let (mut packages_to_install, mut aur_to_install) = if options.dry_run {
+            (packages.to_vec(), aur_packages.to_vec())
+        } else {
+            let cache = PackageCache::open(PackageCache::default_path())?;
+            (
+                cache.filter_missing(packages)?,
+                cache.filter_missing(aur_packages)?,
+            )
+        };
+
+        // Reject unvetted packages before they ever reach the helper, rather
+        // than letting a supply-chain-unreviewed package install silently.
+        let mut vet_failed = Vec::new();
+        if options.require_vetted {
+            let store = PackageAuditStore::load(PackageAuditStore::default_path())?;
+            let vetter = PackageVetter::new(&store, &options.required_vet_criteria);
+
+            let (allowed, rejected) = vetter.partition(&packages_to_install, false);
+            packages_to_install = allowed;
+            vet_failed.extend(rejected);
+
+            let (aur_allowed, aur_rejected) = vetter.partition(&aur_to_install, true);
+            aur_to_install = aur_allowed;
+            vet_failed.extend(aur_rejected);
+        }
+
+        let ((repo_installed, repo_failed), (aur_installed, aur_failed)) = tokio::try_join!(
+            self.install_packages(&packages_to_install, options),
+            self.install_aur_packages(&aur_to_install, options),
+        )?;
+        let commands_executed = self.execute_commands(commands, options.dry_run).await?;
+
+        let mut all_installed = repo_installed;
+        all_installed.extend(aur_installed);
+
+        let mut all_failed: Vec<FailedPackage> = repo_failed
+            .into_iter()
+            .map(|package| FailedPackage {
+                package,
+                reason: "package installation failed".to_string(),
+            })
+            .collect();
+        all_failed.extend(aur_failed.into_iter().map(|package| FailedPackage {
+            package,
+            reason: "AUR package installation failed".to_string(),
+        }));
+        all_failed.extend(vet_failed);
+
+        if !options.dry_run {
+            let mut cache = PackageCache::open(PackageCache::default_path())?;
+            cache.invalidate()?;
+            cache.refresh()?;
+        }
+
+        let duration_secs = start.elapsed().as_secs();
+
+        Ok(SyncReport {
+            packages_installed: all_installed,
+            packages_failed: all_failed,
+            commands_executed,
+            duration_secs,
+        })
+    }
+
+    /// Check if a package is installed
+    pub fn is_installed(&self, package: &str) -> bool {
+        std::process::Command::new("pacman")
+            .args(["-Q", package])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Update system
+    pub fn update_system(&self, dry_run: bool) -> Result<()> {
+        if dry_run {
+            log::info!("DRY RUN: Would update system");
+            return Ok(());
+        }
+
+        let _sudo_loop = SudoLoop::start(Duration::from_secs(default_sudo_refresh_secs()))?;
+
+        log::info!("Updating system with {}", self.helper.command());
+
+        let output = std::process::Command::new(self.helper.command())
+            .args(["-Syu", "--noconfirm"])
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .output()
+            .map_err(|e| S1bCr4ftError::package(format!("Failed to update system: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(S1bCr4ftError::package("System update failed"));
+        }
+
+        let mut cache = PackageCache::open(PackageCache::default_path())?;
+        cache.invalidate()?;
+        cache.refresh()?;
+
+        Ok(())
+    }
+}
+
+impl Default for PackageManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_helper_detection() {
+        let helper = PackageHelper::detect();
+        assert!(matches!(
+            helper,
+            PackageHelper::Pacman
+                | PackageHelper::Paru
+                | PackageHelper::Yay
+                | PackageHelper::Makepkg
+        ));
+    }
+
+    #[test]
+    fn test_makepkg_helper_can_install_aur() {
+        assert!(PackageHelper::Makepkg.can_install_aur());
+    }
+
+    #[test]
+    fn test_sync_options() {
+        let options = SyncOptions {
+            dry_run: true,
+            force: false,
+            parallel: true,
+            ..Default::default()
+        };
+        assert!(options.dry_run);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_install_reports_all_packages_installed() {
+        let manager = PackageManager::with_helper(PackageHelper::Pacman);
+        let options = SyncOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+
+        let (installed, failed) = manager
+            .install_packages(&["vim".to_string(), "git".to_string()], &options)
+            .await
+            .unwrap();
+
+        assert_eq!(installed.len(), 2);
+        assert!(failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sync_rejects_unvetted_packages_when_required() {
+        let manager = PackageManager::with_helper(PackageHelper::Pacman);
+        let options = SyncOptions {
+            dry_run: true,
+            require_vetted: true,
+            ..Default::default()
+        };
+
+        let report = manager
+            .sync(&["vim".to_string()], &[], &[], &options)
+            .await
+            .unwrap();
+
+        assert!(report.packages_installed.is_empty());
+        assert_eq!(report.packages_failed.len(), 1);
+        assert_eq!(report.packages_failed[0].package, "vim");
+        assert!(report.packages_failed[0].reason.contains("vet criteria"));
+    }
+
+    #[tokio::test]
+    async fn test_install_staged_runs_stages_in_order() {
+        let manager = PackageManager::with_helper(PackageHelper::Pacman);
+        let options = SyncOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+
+        let stages = vec![vec!["base".to_string()], vec!["dependent".to_string()]];
+        let (installed, failed) = manager.install_staged(&stages, &options).await.unwrap();
+
+        assert_eq!(installed, vec!["base".to_string(), "dependent".to_string()]);
+        assert!(failed.is_empty());
+    }
+}
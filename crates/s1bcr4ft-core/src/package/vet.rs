@@ -0,0 +1,256 @@
+//! Package supply-chain vetting, a `crate::vet`-style trust layer but keyed
+//! by package name instead of module ID. [`PackageManager::sync`] consults a
+//! [`PackageAuditStore`] when [`crate::package::SyncOptions::require_vetted`]
+//! is set, so an unreviewed dependency doesn't get pulled in silently.
+
+use super::FailedPackage;
+use crate::error::{Result, S1bCr4ftError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A single package audit: the criteria `package` has been reviewed under.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageAudit {
+    pub package: String,
+    pub criteria: Vec<String>,
+    /// Identity that certified this entry (e.g. a GPG key ID), recorded so
+    /// `package_audits.toml` entries carry provenance even though this
+    /// crate's GPG support is presently verification-only.
+    #[serde(default)]
+    pub certified_by: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// `package_audits.toml`: every package audit this project trusts, plus
+/// packages deliberately grandfathered in without one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageAuditStore {
+    #[serde(default)]
+    pub audits: Vec<PackageAudit>,
+    #[serde(default)]
+    pub exemptions: Vec<String>,
+}
+
+impl PackageAuditStore {
+    pub fn default_path() -> PathBuf {
+        crate::default_config_dir().join("package_audits.toml")
+    }
+
+    /// Load the store from `path`, or an empty store if it doesn't exist yet
+    /// (a project with no package_audits.toml simply has no audits).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if !path.as_ref().exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path.as_ref()).map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to read package_audits.toml: {}", e))
+        })?;
+
+        toml::from_str(&content).map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to parse package_audits.toml: {}", e))
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = toml::to_string_pretty(self).map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to serialize package_audits.toml: {}", e))
+        })?;
+
+        fs::write(path.as_ref(), content).map_err(|e| {
+            S1bCr4ftError::package(format!("Failed to write package_audits.toml: {}", e))
+        })?;
+
+        Ok(())
+    }
+
+    /// Record a new audit, replacing any existing entry for the same
+    /// package so re-certifying updates in place rather than accumulating
+    /// stale duplicates.
+    pub fn certify(&mut self, audit: PackageAudit) {
+        self.audits.retain(|a| a.package != audit.package);
+        self.audits.push(audit);
+    }
+
+    fn satisfied_criteria(&self, package: &str) -> HashSet<&str> {
+        self.audits
+            .iter()
+            .filter(|a| a.package == package)
+            .flat_map(|a| a.criteria.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Whether `package` has been audited, across all its entries, under
+    /// every criterion in `required`.
+    pub fn is_satisfied(&self, package: &str, required: &[String]) -> bool {
+        self.missing_criteria(package, required).is_empty()
+    }
+
+    /// Required criteria `package` has NOT been audited for.
+    pub fn missing_criteria(&self, package: &str, required: &[String]) -> Vec<String> {
+        let satisfied = self.satisfied_criteria(package);
+        required
+            .iter()
+            .filter(|c| !satisfied.contains(c.as_str()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Splits packages into those that satisfy a [`PackageAuditStore`]'s vetting
+/// requirement and those that don't.
+pub struct PackageVetter<'a> {
+    store: &'a PackageAuditStore,
+    required_criteria: &'a [String],
+}
+
+impl<'a> PackageVetter<'a> {
+    pub fn new(store: &'a PackageAuditStore, required_criteria: &'a [String]) -> Self {
+        Self {
+            store,
+            required_criteria,
+        }
+    }
+
+    /// Split `packages` into (vetted, rejected). AUR packages implicitly
+    /// require an `aur-trusted` audit on top of `required_criteria`, since
+    /// they're unreviewed upstream; exempted packages always pass.
+    pub fn partition(&self, packages: &[String], is_aur: bool) -> (Vec<String>, Vec<FailedPackage>) {
+        let mut allowed = Vec::new();
+        let mut rejected = Vec::new();
+
+        for package in packages {
+            if self.store.exemptions.iter().any(|e| e == package) {
+                allowed.push(package.clone());
+                continue;
+            }
+
+            let mut required = self.required_criteria.to_vec();
+            if is_aur && !required.iter().any(|c| c == "aur-trusted") {
+                required.push("aur-trusted".to_string());
+            }
+
+            let missing = self.store.missing_criteria(package, &required);
+            if missing.is_empty() {
+                allowed.push(package.clone());
+            } else {
+                let reason = format!("missing vet criteria: {}", missing.join(", "));
+                log::warn!("Package '{}' failed vetting, {}", package, reason);
+                rejected.push(FailedPackage {
+                    package: package.clone(),
+                    reason,
+                });
+            }
+        }
+
+        (allowed, rejected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn sample_audit(package: &str, criteria: &[&str]) -> PackageAudit {
+        PackageAudit {
+            package: package.to_string(),
+            criteria: criteria.iter().map(|c| c.to_string()).collect(),
+            certified_by: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_missing_criteria_reports_unaudited_package() {
+        let store = PackageAuditStore::default();
+        let required = vec!["reviewed".to_string()];
+        assert_eq!(store.missing_criteria("yay-bin", &required), required);
+    }
+
+    #[test]
+    fn test_certify_satisfies_matching_criteria() {
+        let mut store = PackageAuditStore::default();
+        store.certify(sample_audit("yay-bin", &["reviewed", "aur-trusted"]));
+
+        let required = vec!["reviewed".to_string()];
+        assert!(store.is_satisfied("yay-bin", &required));
+    }
+
+    #[test]
+    fn test_recertify_replaces_existing_entry() {
+        let mut store = PackageAuditStore::default();
+        store.certify(sample_audit("vim", &["reviewed"]));
+        store.certify(sample_audit("vim", &["reviewed", "aur-trusted"]));
+
+        assert_eq!(store.audits.len(), 1);
+        assert_eq!(store.audits[0].criteria.len(), 2);
+    }
+
+    #[test]
+    fn test_vetter_allows_exempted_package() {
+        let mut store = PackageAuditStore::default();
+        store.exemptions.push("base".to_string());
+        let required = vec!["reviewed".to_string()];
+        let vetter = PackageVetter::new(&store, &required);
+
+        let (allowed, rejected) = vetter.partition(&["base".to_string()], false);
+        assert_eq!(allowed, vec!["base".to_string()]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn test_vetter_rejects_unaudited_repo_package() {
+        let store = PackageAuditStore::default();
+        let required = vec!["reviewed".to_string()];
+        let vetter = PackageVetter::new(&store, &required);
+
+        let (allowed, rejected) = vetter.partition(&["vim".to_string()], false);
+        assert!(allowed.is_empty());
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].package, "vim");
+        assert!(rejected[0].reason.contains("reviewed"));
+    }
+
+    #[test]
+    fn test_vetter_requires_aur_trusted_for_aur_packages_even_without_explicit_criteria() {
+        let mut store = PackageAuditStore::default();
+        store.certify(sample_audit("yay-bin", &["reviewed"]));
+        let required = vec!["reviewed".to_string()];
+        let vetter = PackageVetter::new(&store, &required);
+
+        let (allowed, rejected) = vetter.partition(&["yay-bin".to_string()], true);
+        assert!(allowed.is_empty());
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].package, "yay-bin");
+        assert!(rejected[0].reason.contains("aur-trusted"));
+
+        store.certify(sample_audit("yay-bin", &["reviewed", "aur-trusted"]));
+        let vetter = PackageVetter::new(&store, &required);
+        let (allowed, rejected) = vetter.partition(&["yay-bin".to_string()], true);
+        assert_eq!(allowed, vec!["yay-bin".to_string()]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let mut store = PackageAuditStore::default();
+        store.certify(sample_audit("vim", &["reviewed"]));
+
+        let temp_file = NamedTempFile::new().unwrap();
+        store.save(temp_file.path()).unwrap();
+        let loaded = PackageAuditStore::load(temp_file.path()).unwrap();
+
+        assert_eq!(loaded.audits.len(), 1);
+        assert_eq!(loaded.audits[0].package, "vim");
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_empty_store() {
+        let store = PackageAuditStore::load("/nonexistent/package_audits.toml").unwrap();
+        assert!(store.audits.is_empty());
+    }
+}
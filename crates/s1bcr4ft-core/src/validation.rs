@@ -1,42 +1,189 @@
 use crate::config::Config;
-use crate::error::Result;
+use miette::{Diagnostic, NamedSource, SourceSpan};
+use thiserror::Error;
 
-#[derive(Debug, Clone)]
-pub struct ValidationError {
-    pub field: String,
-    pub message: String,
+/// A single validation failure, carrying enough span information for
+/// `cmd_validate` to render a caret-annotated snippet of the offending YAML
+/// rather than a bare field name.
+#[derive(Debug, Error, Diagnostic)]
+pub enum ValidationError {
+    #[error("Version cannot be empty")]
+    #[diagnostic(code(s1bcr4ft::config::missing_version))]
+    MissingVersion {
+        #[source_code]
+        source_code: NamedSource<String>,
+        #[label("version must be set here")]
+        span: SourceSpan,
+    },
+
+    #[error("Name cannot be empty")]
+    #[diagnostic(code(s1bcr4ft::config::missing_name))]
+    MissingName {
+        #[source_code]
+        source_code: NamedSource<String>,
+        #[label("name must be set here")]
+        span: SourceSpan,
+    },
+
+    #[error("At least one module must be specified")]
+    #[diagnostic(code(s1bcr4ft::config::empty_modules))]
+    EmptyModules {
+        #[source_code]
+        source_code: NamedSource<String>,
+        #[label("modules list is empty here")]
+        span: SourceSpan,
+    },
+}
+
+impl ValidationError {
+    /// The config field this diagnostic refers to, for callers that just
+    /// want a plain name rather than rendering the miette snippet.
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationError::MissingVersion { .. } => "version",
+            ValidationError::MissingName { .. } => "name",
+            ValidationError::EmptyModules { .. } => "modules",
+        }
+    }
 }
 
 pub struct ConfigValidator;
 
 impl ConfigValidator {
-    pub fn validate(config: &Config) -> Result<Vec<ValidationError>> {
+    /// Validate an already-parsed `config` against the rules below,
+    /// pointing each failure at its location in `source` (the raw YAML
+    /// `config` was parsed from) so diagnostics can be rendered with
+    /// `miette::Report`.
+    ///
+    /// `source_name` is used as the `NamedSource` label (typically the
+    /// config file's path).
+    pub fn validate(config: &Config, source: &str, source_name: &str) -> Vec<ValidationError> {
         let mut errors = Vec::new();
+        let named_source = || NamedSource::new(source_name.to_string(), source.to_string());
 
-        // Validate version
         if config.version.is_empty() {
-            errors.push(ValidationError {
-                field: "version".to_string(),
-                message: "Version cannot be empty".to_string(),
+            errors.push(ValidationError::MissingVersion {
+                source_code: named_source(),
+                span: span_for_key(source, "version"),
             });
         }
 
-        // Validate name
         if config.name.is_empty() {
-            errors.push(ValidationError {
-                field: "name".to_string(),
-                message: "Name cannot be empty".to_string(),
+            errors.push(ValidationError::MissingName {
+                source_code: named_source(),
+                span: span_for_key(source, "name"),
             });
         }
 
-        // Validate modules
         if config.modules.is_empty() {
-            errors.push(ValidationError {
-                field: "modules".to_string(),
-                message: "At least one module must be specified".to_string(),
+            errors.push(ValidationError::EmptyModules {
+                source_code: named_source(),
+                span: span_for_key(source, "modules"),
             });
         }
 
-        Ok(errors)
+        errors
+    }
+}
+
+/// Find the byte span of a top-level `key:` in raw YAML by re-scanning the
+/// source line by line, since `Config` no longer carries its own position
+/// information once deserialized. Falls back to an empty span at the start
+/// of the file when the key is entirely absent.
+fn span_for_key(source: &str, key: &str) -> SourceSpan {
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let without_indent = trimmed.trim_start();
+        let indent = trimmed.len() - without_indent.len();
+
+        if let Some(rest) = without_indent.strip_prefix(key) {
+            if indent == 0 && rest.starts_with(':') {
+                let start = offset + indent;
+                return SourceSpan::new(start.into(), key.len());
+            }
+        }
+
+        offset += line.len();
+    }
+
+    SourceSpan::new(0.into(), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigLoader;
+
+    fn config_with(name: &str, version: &str, modules: Vec<&str>) -> Config {
+        Config {
+            version: version.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            modules: modules.into_iter().map(|m| m.to_string()).collect(),
+            dotfiles: Vec::new(),
+            hooks: Default::default(),
+            options: Default::default(),
+            security: Default::default(),
+            aliases: Default::default(),
+            module_profiles: Default::default(),
+            include: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_config_has_no_errors() {
+        let config = config_with("my-project", "1.0", vec!["core/base-system"]);
+        let errors = ConfigValidator::validate(&config, "version: \"1.0\"\n", "config.yml");
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_missing_name_reports_expected_field() {
+        let config = config_with("", "1.0", vec!["core/base-system"]);
+        let source = "version: \"1.0\"\nname: \"\"\nmodules:\n  - core/base-system\n";
+        let errors = ConfigValidator::validate(&config, source, "config.yml");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field(), "name");
+    }
+
+    #[test]
+    fn test_empty_modules_reports_expected_field() {
+        let config = config_with("my-project", "1.0", vec![]);
+        let source = "version: \"1.0\"\nname: \"my-project\"\nmodules: []\n";
+        let errors = ConfigValidator::validate(&config, source, "config.yml");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field(), "modules");
+    }
+
+    #[test]
+    fn test_span_for_key_locates_top_level_key() {
+        let source = "version: \"1.0\"\nname: \"my-project\"\n";
+        let span = span_for_key(source, "name");
+        assert_eq!(span.offset(), "version: \"1.0\"\n".len());
+        assert_eq!(span.len(), "name".len());
+    }
+
+    #[test]
+    fn test_span_for_key_falls_back_when_absent() {
+        let source = "version: \"1.0\"\n";
+        let span = span_for_key(source, "name");
+        assert_eq!(span.offset(), 0);
+        assert_eq!(span.len(), 0);
+    }
+
+    #[test]
+    fn test_real_config_file_reparsed_for_validation() {
+        let source = "version: \"1.0\"\nname: \"\"\nmodules: []\n";
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), source).unwrap();
+
+        let config = ConfigLoader::load(temp_file.path()).unwrap();
+        let errors = ConfigValidator::validate(&config, source, "config.yml");
+
+        let fields: Vec<_> = errors.iter().map(|e| e.field()).collect();
+        assert_eq!(fields, vec!["name", "modules"]);
     }
 }
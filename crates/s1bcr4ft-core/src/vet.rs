@@ -0,0 +1,246 @@
+use crate::error::{Result, S1bCr4ftError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// A single audit: the criteria a module has been reviewed under, and the
+/// exact upstream source that review covered, cargo-vet style.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModuleAudit {
+    pub module_id: String,
+    pub criteria: Vec<String>,
+    pub source_url: String,
+    pub pinned_hash: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// A trusted audit set imported from another maintainer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditImport {
+    pub url: String,
+}
+
+/// `audits.toml`: every module audit this project trusts, plus imports of
+/// other maintainers' audit sets.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditStore {
+    #[serde(default)]
+    pub audits: Vec<ModuleAudit>,
+    #[serde(default)]
+    pub imports: Vec<AuditImport>,
+}
+
+impl AuditStore {
+    /// Load the store from `path`, or an empty store if it doesn't exist
+    /// yet (a project with no audits.toml simply has no audits).
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if !path.as_ref().exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path.as_ref())
+            .map_err(|e| S1bCr4ftError::audit(format!("Failed to read audits.toml: {}", e)))?;
+
+        toml::from_str(&content)
+            .map_err(|e| S1bCr4ftError::audit(format!("Failed to parse audits.toml: {}", e)))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| S1bCr4ftError::audit(format!("Failed to serialize audits.toml: {}", e)))?;
+
+        fs::write(path.as_ref(), content)
+            .map_err(|e| S1bCr4ftError::audit(format!("Failed to write audits.toml: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Record a new audit, replacing any existing entry for the same
+    /// module + source URL pair so re-certifying updates in place rather
+    /// than accumulating stale duplicates.
+    pub fn certify(&mut self, audit: ModuleAudit) {
+        self.audits
+            .retain(|a| !(a.module_id == audit.module_id && a.source_url == audit.source_url));
+        self.audits.push(audit);
+    }
+
+    fn satisfied_criteria(&self, module_id: &str) -> HashSet<&str> {
+        self.audits
+            .iter()
+            .filter(|a| a.module_id == module_id)
+            .flat_map(|a| a.criteria.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Whether `module_id` has been audited, across all its entries, under
+    /// every criterion in `required`.
+    pub fn is_satisfied(&self, module_id: &str, required: &[String]) -> bool {
+        self.missing_criteria(module_id, required).is_empty()
+    }
+
+    /// Required criteria `module_id` has NOT been audited for.
+    pub fn missing_criteria(&self, module_id: &str, required: &[String]) -> Vec<String> {
+        let satisfied = self.satisfied_criteria(module_id);
+        required
+            .iter()
+            .filter(|c| !satisfied.contains(c.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Pull a trusted audit set from another maintainer and merge its
+    /// entries into this store, recording the import for provenance.
+    #[cfg(feature = "remote-modules")]
+    pub fn import_from_url(&mut self, url: &str) -> Result<()> {
+        let text = reqwest::blocking::get(url)?.text()?;
+        let imported: AuditStore = toml::from_str(&text).map_err(|e| {
+            S1bCr4ftError::audit(format!("Failed to parse imported audits: {}", e))
+        })?;
+
+        for audit in imported.audits {
+            self.certify(audit);
+        }
+        self.imports.push(AuditImport {
+            url: url.to_string(),
+        });
+
+        Ok(())
+    }
+}
+
+/// Checks a set of module IDs against an [`AuditStore`], honoring
+/// config-level exemptions.
+pub struct ModuleVetter<'a> {
+    store: &'a AuditStore,
+    required_criteria: &'a [String],
+    exemptions: &'a [String],
+}
+
+impl<'a> ModuleVetter<'a> {
+    pub fn new(
+        store: &'a AuditStore,
+        required_criteria: &'a [String],
+        exemptions: &'a [String],
+    ) -> Self {
+        Self {
+            store,
+            required_criteria,
+            exemptions,
+        }
+    }
+
+    /// Reject the first module lacking a satisfying audit for the required
+    /// criteria, unless it's listed in `exemptions`.
+    pub fn check(&self, module_ids: &[String]) -> Result<()> {
+        for id in module_ids {
+            if self.exemptions.iter().any(|e| e == id) {
+                continue;
+            }
+
+            let missing = self.store.missing_criteria(id, self.required_criteria);
+            if !missing.is_empty() {
+                return Err(S1bCr4ftError::audit(format!(
+                    "Module '{}' is missing required vet criteria: {}. Certify it with \
+                     `s1bcr4ft vet certify {}` or add it to security.vet_exemptions",
+                    id,
+                    missing.join(", "),
+                    id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn sample_audit(module_id: &str, criteria: &[&str]) -> ModuleAudit {
+        ModuleAudit {
+            module_id: module_id.to_string(),
+            criteria: criteria.iter().map(|c| c.to_string()).collect(),
+            source_url: "https://example.com/sliver-c2".to_string(),
+            pinned_hash: "deadbeef".to_string(),
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_missing_criteria_reports_unaudited_requirement() {
+        let store = AuditStore::default();
+        let required = vec!["safe-to-install".to_string()];
+        assert_eq!(
+            store.missing_criteria("red-team/c2-frameworks/sliver-c2", &required),
+            required
+        );
+    }
+
+    #[test]
+    fn test_certify_satisfies_matching_criteria() {
+        let mut store = AuditStore::default();
+        store.certify(sample_audit(
+            "red-team/c2-frameworks/sliver-c2",
+            &["safe-to-install", "source-verified"],
+        ));
+
+        let required = vec!["safe-to-install".to_string()];
+        assert!(store.is_satisfied("red-team/c2-frameworks/sliver-c2", &required));
+    }
+
+    #[test]
+    fn test_recertify_replaces_existing_entry_for_same_source() {
+        let mut store = AuditStore::default();
+        store.certify(sample_audit("core/base-system", &["safe-to-install"]));
+        store.certify(sample_audit(
+            "core/base-system",
+            &["safe-to-install", "source-verified"],
+        ));
+
+        assert_eq!(store.audits.len(), 1);
+        assert_eq!(store.audits[0].criteria.len(), 2);
+    }
+
+    #[test]
+    fn test_module_vetter_allows_exempted_module() {
+        let store = AuditStore::default();
+        let required = vec!["safe-to-install".to_string()];
+        let exemptions = vec!["core/base-system".to_string()];
+        let vetter = ModuleVetter::new(&store, &required, &exemptions);
+
+        assert!(vetter.check(&["core/base-system".to_string()]).is_ok());
+    }
+
+    #[test]
+    fn test_module_vetter_rejects_unaudited_module() {
+        let store = AuditStore::default();
+        let required = vec!["safe-to-install".to_string()];
+        let vetter = ModuleVetter::new(&store, &required, &[]);
+
+        let result = vetter.check(&["red-team/c2-frameworks/sliver-c2".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let mut store = AuditStore::default();
+        store.certify(sample_audit("core/base-system", &["safe-to-install"]));
+
+        let temp_file = NamedTempFile::new().unwrap();
+        store.save(temp_file.path()).unwrap();
+        let loaded = AuditStore::load(temp_file.path()).unwrap();
+
+        assert_eq!(loaded.audits.len(), 1);
+        assert_eq!(loaded.audits[0].module_id, "core/base-system");
+    }
+
+    #[test]
+    fn test_load_missing_file_returns_empty_store() {
+        let store = AuditStore::load("/nonexistent/audits.toml").unwrap();
+        assert!(store.audits.is_empty());
+    }
+}
@@ -6,10 +6,44 @@
 //! - Test fixtures for configs and modules
 //! - Assertion helpers
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Mutex;
 use tempfile::TempDir;
 
+/// Dependency/conflict graph entry for a single mock package, mirroring the
+/// `dependencies`/`conflicts`/`aur_packages` fields already present on
+/// `ModuleFixture` modules, so `PacmanMock` can model a real transaction
+/// instead of a flat install list.
+#[derive(Debug, Clone, Default)]
+pub struct MockPackageSpec {
+    pub dependencies: Vec<String>,
+    pub conflicts: Vec<String>,
+    pub aur_packages: Vec<String>,
+}
+
+/// When a mock operation should fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailMode {
+    Never,
+    Always,
+    /// Fail once the *n*th package (0-indexed) in a transaction is reached,
+    /// so tests can assert that an earlier partial install is left in place
+    /// until `rollback()` is called.
+    AtStep(usize),
+}
+
+/// Common interface for driving package operations in tests, implemented by
+/// both [`PacmanMock`] (fast, in-memory) and [`ContainerPackageManager`]
+/// (real `pacman` inside a throwaway container). Test bodies written against
+/// this trait run unchanged against either backend.
+pub trait PackageManager {
+    fn install(&self, package: &str) -> Result<(), String>;
+    fn install_packages(&self, packages: &[&str]) -> Result<Vec<String>, String>;
+    fn is_installed(&self, package: &str) -> bool;
+    fn list_installed(&self) -> Vec<String>;
+}
+
 /// Test harness for simulating pacman operations without root access
 pub struct PacmanMock {
     /// Temporary directory for mock operations
@@ -18,8 +52,14 @@ pub struct PacmanMock {
     installed_packages: Mutex<Vec<String>>,
     /// Simulated available packages
     available_packages: Vec<String>,
+    /// Dependency/conflict graph for packages that participate in a
+    /// `transaction()`. Packages with no entry here are treated as leaves.
+    package_specs: HashMap<String, MockPackageSpec>,
     /// Whether operations should fail
-    should_fail: Mutex<bool>,
+    should_fail: Mutex<FailMode>,
+    /// Snapshot of `installed_packages` taken at the start of the most
+    /// recent `transaction()`, consumed by `rollback()`.
+    pre_transaction_snapshot: Mutex<Option<Vec<String>>>,
 }
 
 impl PacmanMock {
@@ -42,7 +82,9 @@ impl PacmanMock {
                 "hyprland".to_string(),
                 "wayland".to_string(),
             ],
-            should_fail: Mutex::new(false),
+            package_specs: HashMap::new(),
+            should_fail: Mutex::new(FailMode::Never),
+            pre_transaction_snapshot: Mutex::new(None),
         }
     }
 
@@ -53,9 +95,27 @@ impl PacmanMock {
         mock
     }
 
+    /// Create a mock whose packages carry a dependency/conflict graph, for
+    /// exercising `transaction()`'s resolution and atomicity. Package IDs
+    /// appearing as keys or as dependencies/conflicts are automatically
+    /// treated as available.
+    pub fn with_module_packages(specs: HashMap<String, MockPackageSpec>) -> Self {
+        let mut mock = Self::new();
+        mock.available_packages = specs.keys().cloned().collect();
+        for spec in specs.values() {
+            for id in spec.dependencies.iter().chain(&spec.conflicts) {
+                if !mock.available_packages.contains(id) {
+                    mock.available_packages.push(id.clone());
+                }
+            }
+        }
+        mock.package_specs = specs;
+        mock
+    }
+
     /// Simulate installing a package
     pub fn install(&self, package: &str) -> Result<(), String> {
-        if *self.should_fail.lock().unwrap() {
+        if *self.should_fail.lock().unwrap() == FailMode::Always {
             return Err("Mock failure triggered".to_string());
         }
 
@@ -98,13 +158,119 @@ impl PacmanMock {
 
     /// Set whether operations should fail
     pub fn set_should_fail(&self, should_fail: bool) {
-        *self.should_fail.lock().unwrap() = should_fail;
+        *self.should_fail.lock().unwrap() = if should_fail {
+            FailMode::Always
+        } else {
+            FailMode::Never
+        };
+    }
+
+    /// Make `transaction()` fail once it reaches the package at `step`
+    /// (0-indexed, in transitive-closure resolution order), so tests can
+    /// verify a mid-transaction failure leaves the earlier packages
+    /// installed until `rollback()` is called.
+    pub fn set_should_fail_at_step(&self, step: usize) {
+        *self.should_fail.lock().unwrap() = FailMode::AtStep(step);
     }
 
     /// Get the temp directory path
     pub fn temp_path(&self) -> PathBuf {
         self.temp_dir.path().to_path_buf()
     }
+
+    /// Resolve the transitive dependency closure of `packages`, depth-first,
+    /// erroring if any package (or transitive dependency) is unknown.
+    fn resolve_closure(&self, packages: &[&str]) -> Result<Vec<String>, String> {
+        let mut closure = Vec::new();
+        let mut seen = std::collections::HashSet::new();
+
+        fn visit(
+            mock: &PacmanMock,
+            id: &str,
+            closure: &mut Vec<String>,
+            seen: &mut std::collections::HashSet<String>,
+        ) -> Result<(), String> {
+            if seen.contains(id) {
+                return Ok(());
+            }
+            if !mock.available_packages.contains(&id.to_string()) {
+                return Err(format!("Package not found: {}", id));
+            }
+            seen.insert(id.to_string());
+
+            if let Some(spec) = mock.package_specs.get(id) {
+                for dep in &spec.dependencies {
+                    visit(mock, dep, closure, seen)?;
+                }
+            }
+
+            closure.push(id.to_string());
+            Ok(())
+        }
+
+        for pkg in packages {
+            visit(self, pkg, &mut closure, &mut seen)?;
+        }
+
+        Ok(closure)
+    }
+
+    /// Check that no two packages in `closure` conflict with each other
+    fn check_closure_conflicts(&self, closure: &[String]) -> Result<(), String> {
+        for pkg in closure {
+            let Some(spec) = self.package_specs.get(pkg) else {
+                continue;
+            };
+            for conflict in &spec.conflicts {
+                if closure.contains(conflict) {
+                    return Err(format!(
+                        "Conflict detected: {} conflicts with {}",
+                        pkg, conflict
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Install `packages` and their transitive dependencies as a single
+    /// transaction: resolves the closure, fails atomically (installing
+    /// nothing) if any package is missing or two resolved packages conflict,
+    /// then installs them one at a time, honoring `set_should_fail_at_step`.
+    ///
+    /// On any failure the mock may be left with a partial install (if the
+    /// failure happened mid-install); call `rollback()` to restore the
+    /// pre-transaction state.
+    pub fn transaction(&self, packages: &[&str]) -> Result<Vec<String>, String> {
+        let snapshot = self.installed_packages.lock().unwrap().clone();
+        *self.pre_transaction_snapshot.lock().unwrap() = Some(snapshot);
+
+        let closure = self.resolve_closure(packages)?;
+        self.check_closure_conflicts(&closure)?;
+
+        let mut installed_this_txn = Vec::new();
+        for (step, pkg) in closure.iter().enumerate() {
+            if *self.should_fail.lock().unwrap() == FailMode::AtStep(step) {
+                return Err(format!("Mock failure triggered at step {}", step));
+            }
+
+            let mut installed = self.installed_packages.lock().unwrap();
+            if !installed.contains(pkg) {
+                installed.push(pkg.clone());
+            }
+            installed_this_txn.push(pkg.clone());
+        }
+
+        Ok(installed_this_txn)
+    }
+
+    /// Restore `installed_packages` to the snapshot taken at the start of
+    /// the most recent `transaction()`. A no-op if no transaction has run.
+    pub fn rollback(&self) {
+        if let Some(snapshot) = self.pre_transaction_snapshot.lock().unwrap().take() {
+            *self.installed_packages.lock().unwrap() = snapshot;
+        }
+    }
 }
 
 impl Default for PacmanMock {
@@ -113,6 +279,181 @@ impl Default for PacmanMock {
     }
 }
 
+impl PackageManager for PacmanMock {
+    fn install(&self, package: &str) -> Result<(), String> {
+        self.install(package)
+    }
+
+    fn install_packages(&self, packages: &[&str]) -> Result<Vec<String>, String> {
+        self.install_packages(packages)
+    }
+
+    fn is_installed(&self, package: &str) -> bool {
+        self.is_installed(package)
+    }
+
+    fn list_installed(&self) -> Vec<String> {
+        self.list_installed()
+    }
+}
+
+/// Which container runtime `ContainerPackageManager` talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    fn detect() -> Option<Self> {
+        if Self::binary_available("docker") {
+            Some(ContainerRuntime::Docker)
+        } else if Self::binary_available("podman") {
+            Some(ContainerRuntime::Podman)
+        } else {
+            None
+        }
+    }
+
+    fn binary_available(bin: &str) -> bool {
+        std::process::Command::new(bin)
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    fn command(&self) -> &str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+/// Arch Linux base image used for real `pacman` operations in tests
+const ARCH_CONTAINER_IMAGE: &str = "archlinux:base";
+
+/// Test harness that runs real `pacman` inside a throwaway Arch Linux
+/// container, started fresh per-test and torn down on drop. Mirrors the
+/// per-test container harness used by `cargo-test-support`'s apache/sshd
+/// fixtures, but for a package manager instead of a service.
+///
+/// Use [`ContainerPackageManager::try_new`] rather than a plain constructor:
+/// it returns `None` (instead of panicking) when neither Docker nor Podman is
+/// installed, so tests can skip gracefully on machines without a container
+/// runtime.
+pub struct ContainerPackageManager {
+    runtime: ContainerRuntime,
+    container_id: String,
+    /// Reused for bind-mounting fixture configs into the container
+    pub temp_dir: TempDir,
+}
+
+impl ContainerPackageManager {
+    /// Pull the Arch image (if not already cached) and start a fresh, disposable
+    /// container. Returns `None` if no container runtime is available.
+    pub fn try_new() -> Option<Self> {
+        let runtime = ContainerRuntime::detect()?;
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+
+        // Pulling is a no-op once the image is cached locally.
+        let _ = std::process::Command::new(runtime.command())
+            .args(["pull", ARCH_CONTAINER_IMAGE])
+            .output();
+
+        let output = std::process::Command::new(runtime.command())
+            .args([
+                "run",
+                "-d",
+                "--rm",
+                "-v",
+                &format!("{}:/fixtures", temp_dir.path().display()),
+                ARCH_CONTAINER_IMAGE,
+                "sleep",
+                "infinity",
+            ])
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let container_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if container_id.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            runtime,
+            container_id,
+            temp_dir,
+        })
+    }
+
+    /// Path to the bind-mounted fixture directory, as seen from the host
+    pub fn temp_path(&self) -> PathBuf {
+        self.temp_dir.path().to_path_buf()
+    }
+
+    fn exec(&self, args: &[&str]) -> std::io::Result<std::process::Output> {
+        let mut cmd_args = vec!["exec", self.container_id.as_str()];
+        cmd_args.extend(args);
+        std::process::Command::new(self.runtime.command())
+            .args(&cmd_args)
+            .output()
+    }
+}
+
+impl PackageManager for ContainerPackageManager {
+    fn install(&self, package: &str) -> Result<(), String> {
+        let output = self
+            .exec(&["pacman", "-Sy", "--noconfirm", package])
+            .map_err(|e| format!("Failed to exec pacman: {}", e))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
+    }
+
+    fn install_packages(&self, packages: &[&str]) -> Result<Vec<String>, String> {
+        let mut installed = Vec::new();
+        for package in packages {
+            self.install(package)?;
+            installed.push(package.to_string());
+        }
+        Ok(installed)
+    }
+
+    fn is_installed(&self, package: &str) -> bool {
+        self.exec(&["pacman", "-Q", package])
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    fn list_installed(&self) -> Vec<String> {
+        let Ok(output) = self.exec(&["pacman", "-Q"]) else {
+            return Vec::new();
+        };
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_whitespace().next().map(|s| s.to_string()))
+            .collect()
+    }
+}
+
+impl Drop for ContainerPackageManager {
+    fn drop(&mut self) {
+        let _ = std::process::Command::new(self.runtime.command())
+            .args(["kill", &self.container_id])
+            .output();
+    }
+}
+
 /// Test fixture for creating temporary config files
 pub struct ConfigFixture {
     pub temp_dir: TempDir,
@@ -423,12 +764,124 @@ packages:
         }
     }
 
+    /// Create a module fixture with several versions of a shared dependency,
+    /// exercising SemVer-constrained backtracking
+    pub fn with_versioned_modules() -> Self {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let modules_path = temp_dir.path().join("modules");
+
+        // Three versions of the same module, registered under the same id
+        Self::create_module_in(
+            &modules_path,
+            "lib/shared-v1",
+            r#"
+id: lib/shared
+name: Shared Library
+description: Shared library dependency
+category: lib
+version: "1.0.0"
+packages:
+  - shared-lib
+"#,
+        );
+
+        Self::create_module_in(
+            &modules_path,
+            "lib/shared-v2",
+            r#"
+id: lib/shared
+name: Shared Library
+description: Shared library dependency
+category: lib
+version: "2.0.0"
+packages:
+  - shared-lib
+"#,
+        );
+
+        Self::create_module_in(
+            &modules_path,
+            "lib/shared-v3",
+            r#"
+id: lib/shared
+name: Shared Library
+description: Shared library dependency
+category: lib
+version: "2.5.0"
+packages:
+  - shared-lib
+"#,
+        );
+
+        Self::create_module(
+            &modules_path,
+            "app/alpha",
+            r#"
+id: app/alpha
+name: Alpha
+description: Wants any 2.x of the shared lib
+category: app
+version: "1.0.0"
+dependencies:
+  lib/shared: ">=2.0, <3.0"
+packages:
+  - alpha
+"#,
+        );
+
+        Self::create_module(
+            &modules_path,
+            "app/beta",
+            r#"
+id: app/beta
+name: Beta
+description: Wants only 2.0.x of the shared lib, forcing a downgrade
+category: app
+version: "1.0.0"
+dependencies:
+  lib/shared: ">=2.0, <2.5.0"
+packages:
+  - beta
+"#,
+        );
+
+        Self::create_module(
+            &modules_path,
+            "app/gamma",
+            r#"
+id: app/gamma
+name: Gamma
+description: Wants a 1.x of the shared lib, incompatible with alpha
+category: app
+version: "1.0.0"
+dependencies:
+  lib/shared: ">=1.0, <2.0"
+packages:
+  - gamma
+"#,
+        );
+
+        Self {
+            temp_dir,
+            modules_path,
+        }
+    }
+
     fn create_module(base: &std::path::Path, id: &str, content: &str) {
         let module_path = base.join(id);
         std::fs::create_dir_all(&module_path).expect("Failed to create module dir");
         std::fs::write(module_path.join("module.yml"), content).expect("Failed to write module");
     }
 
+    /// Like `create_module`, but lets the on-disk directory differ from the
+    /// module's `id` field — needed to register multiple versions of the
+    /// same id without their files clobbering each other.
+    fn create_module_in(base: &std::path::Path, dir: &str, content: &str) {
+        let module_path = base.join(dir);
+        std::fs::create_dir_all(&module_path).expect("Failed to create module dir");
+        std::fs::write(module_path.join("module.yml"), content).expect("Failed to write module");
+    }
+
     pub fn path(&self) -> &std::path::Path {
         &self.modules_path
     }
@@ -436,7 +889,7 @@ packages:
 
 /// Assertion helpers
 pub mod assert {
-    use std::path::Path;
+    use std::path::{Path, PathBuf};
 
     /// Assert that a file exists
     pub fn file_exists(path: &Path) {
@@ -477,6 +930,70 @@ pub mod assert {
             error
         );
     }
+
+    /// Compare `actual` against the checked-in snapshot `tests/snapshots/<name>.txt`.
+    ///
+    /// Modeled on compiletest's expected-output files: set `S1BCR4FT_BLESS=1`
+    /// to (re)write the snapshot instead of asserting against it, then review
+    /// the diff like any other checked-in fixture change.
+    pub fn snapshot(name: &str, actual: &str) {
+        let path = snapshot_path(name);
+
+        if std::env::var("S1BCR4FT_BLESS").as_deref() == Ok("1") {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("Failed to create snapshots directory");
+            }
+            std::fs::write(&path, actual).expect("Failed to write snapshot");
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "Snapshot '{}' does not exist at {}. Run with S1BCR4FT_BLESS=1 to create it.",
+                name,
+                path.display()
+            )
+        });
+
+        if expected != actual {
+            panic!(
+                "Snapshot '{}' mismatch (run with S1BCR4FT_BLESS=1 to update):\n{}",
+                name,
+                unified_diff(&expected, actual)
+            );
+        }
+    }
+
+    fn snapshot_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("snapshots")
+            .join(format!("{}.txt", name))
+    }
+
+    /// Minimal line-oriented diff: one `-`/`+`/` ` line per position rather
+    /// than dumping both blobs in full.
+    fn unified_diff(expected: &str, actual: &str) -> String {
+        let expected_lines: Vec<&str> = expected.lines().collect();
+        let actual_lines: Vec<&str> = actual.lines().collect();
+        let max_len = expected_lines.len().max(actual_lines.len());
+
+        let mut out = String::new();
+        for i in 0..max_len {
+            match (expected_lines.get(i), actual_lines.get(i)) {
+                (Some(e), Some(a)) if e == a => out.push_str(&format!("  {}\n", e)),
+                (Some(e), Some(a)) => {
+                    out.push_str(&format!("- {}\n", e));
+                    out.push_str(&format!("+ {}\n", a));
+                }
+                (Some(e), None) => out.push_str(&format!("- {}\n", e)),
+                (None, Some(a)) => out.push_str(&format!("+ {}\n", a)),
+                (None, None) => {}
+            }
+        }
+
+        out
+    }
 }
 
 #[cfg(test)]
@@ -511,6 +1028,83 @@ mod tests {
         assert!(result.is_err());
     }
 
+    fn sample_module_packages() -> HashMap<String, MockPackageSpec> {
+        let mut specs = HashMap::new();
+        specs.insert(
+            "base".to_string(),
+            MockPackageSpec::default(),
+        );
+        specs.insert(
+            "rust".to_string(),
+            MockPackageSpec {
+                dependencies: vec!["base".to_string()],
+                ..Default::default()
+            },
+        );
+        specs.insert(
+            "package-a".to_string(),
+            MockPackageSpec {
+                conflicts: vec!["package-b".to_string()],
+                ..Default::default()
+            },
+        );
+        specs.insert(
+            "package-b".to_string(),
+            MockPackageSpec {
+                conflicts: vec!["package-a".to_string()],
+                ..Default::default()
+            },
+        );
+        specs
+    }
+
+    #[test]
+    fn test_transaction_resolves_dependency_closure() {
+        let mock = PacmanMock::with_module_packages(sample_module_packages());
+
+        let installed = mock.transaction(&["rust"]).expect("Transaction failed");
+
+        assert_eq!(installed, vec!["base".to_string(), "rust".to_string()]);
+        assert!(mock.is_installed("base"));
+        assert!(mock.is_installed("rust"));
+    }
+
+    #[test]
+    fn test_transaction_fails_atomically_on_conflict() {
+        let mock = PacmanMock::with_module_packages(sample_module_packages());
+
+        let result = mock.transaction(&["package-a", "package-b"]);
+
+        assert!(result.is_err());
+        assert!(mock.list_installed().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_fails_atomically_on_missing_package() {
+        let mock = PacmanMock::with_module_packages(sample_module_packages());
+
+        let result = mock.transaction(&["not-a-real-package"]);
+
+        assert!(result.is_err());
+        assert!(mock.list_installed().is_empty());
+    }
+
+    #[test]
+    fn test_transaction_mid_step_failure_then_rollback() {
+        let mock = PacmanMock::with_module_packages(sample_module_packages());
+
+        // "rust" resolves to ["base", "rust"]; fail once step 1 ("rust") is reached.
+        mock.set_should_fail_at_step(1);
+        let result = mock.transaction(&["rust"]);
+
+        assert!(result.is_err());
+        // The partial install (just "base") is left in place until rollback
+        assert_eq!(mock.list_installed(), vec!["base".to_string()]);
+
+        mock.rollback();
+        assert!(mock.list_installed().is_empty());
+    }
+
     #[test]
     fn test_config_fixture_minimal() {
         let fixture = ConfigFixture::minimal();
@@ -181,24 +181,28 @@ fn test_audit_logger_integration() {
 }
 
 /// Test dry run mode
-#[test]
-fn test_dry_run_mode() {
+#[tokio::test]
+async fn test_dry_run_mode() {
     let mock = PacmanMock::new();
 
     let options = SyncOptions {
         dry_run: true,
         force: false,
         parallel: false,
+        ..Default::default()
     };
 
     let manager = PackageManager::with_helper(PackageHelper::Pacman);
 
     // In dry run mode, nothing should actually be installed
-    let result = manager.install_packages(&["vim".to_string(), "git".to_string()], &options);
+    let result = manager
+        .install_packages(&["vim".to_string(), "git".to_string()], &options)
+        .await;
 
     assert!(result.is_ok());
-    let installed = result.unwrap();
+    let (installed, failed) = result.unwrap();
     assert_eq!(installed.len(), 2);
+    assert!(failed.is_empty());
 
     // But mock shouldn't have them
     assert!(!mock.is_installed("vim"));
@@ -276,14 +280,15 @@ fn test_complex_dependency_chain() {
 }
 
 /// Test sync report generation
-#[test]
-fn test_sync_report_generation() {
+#[tokio::test]
+async fn test_sync_report_generation() {
     let manager = PackageManager::with_helper(PackageHelper::Pacman);
 
     let options = SyncOptions {
         dry_run: true,
         force: false,
         parallel: true,
+        ..Default::default()
     };
 
     let report = manager
@@ -293,6 +298,7 @@ fn test_sync_report_generation() {
             &["echo test".to_string()],
             &options,
         )
+        .await
         .expect("Sync failed");
 
     assert!(!report.packages_installed.is_empty());
@@ -8,7 +8,7 @@
 
 mod common;
 
-use common::ModuleFixture;
+use common::{assert, ModuleFixture};
 use s1bcr4ft_core::module::{ModuleRegistry, ModuleResolver};
 
 /// Test loading modules from directory
@@ -296,6 +296,68 @@ fn test_module_with_aur_packages() {
     assert!(module.aur_packages.contains(&"sliver".to_string()));
 }
 
+/// Test that the newest compatible version is picked when there's no conflict
+#[test]
+fn test_resolve_versions_picks_newest_compatible() {
+    let fixture = ModuleFixture::with_versioned_modules();
+
+    let mut registry = ModuleRegistry::new(fixture.path());
+    registry.load_all().unwrap();
+
+    let resolver = ModuleResolver::new(&registry);
+
+    let resolved = resolver
+        .resolve_versions(&["app/alpha".to_string()])
+        .expect("Failed to resolve versions");
+
+    let shared = resolved
+        .iter()
+        .find(|r| r.id == "lib/shared")
+        .expect("lib/shared should be resolved");
+    assert_eq!(shared.version, "2.5.0");
+}
+
+/// Test that resolution backtracks to an older version when a second
+/// dependent can't use the greedily-picked newest one
+#[test]
+fn test_resolve_versions_backtracks_on_narrower_requirement() {
+    let fixture = ModuleFixture::with_versioned_modules();
+
+    let mut registry = ModuleRegistry::new(fixture.path());
+    registry.load_all().unwrap();
+
+    let resolver = ModuleResolver::new(&registry);
+
+    let resolved = resolver
+        .resolve_versions(&["app/alpha".to_string(), "app/beta".to_string()])
+        .expect("Failed to resolve versions");
+
+    let shared = resolved
+        .iter()
+        .find(|r| r.id == "lib/shared")
+        .expect("lib/shared should be resolved");
+    // alpha alone would pick 2.5.0, but beta requires <2.5.0
+    assert_eq!(shared.version, "2.0.0");
+}
+
+/// Test that an unsatisfiable set of version requirements produces a
+/// descriptive error
+#[test]
+fn test_resolve_versions_unsatisfiable_requirements() {
+    let fixture = ModuleFixture::with_versioned_modules();
+
+    let mut registry = ModuleRegistry::new(fixture.path());
+    registry.load_all().unwrap();
+
+    let resolver = ModuleResolver::new(&registry);
+
+    let result = resolver.resolve_versions(&["app/alpha".to_string(), "app/gamma".to_string()]);
+
+    assert!(result.is_err());
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("lib/shared"));
+}
+
 /// Test resolving multiple times (caching/consistency)
 #[test]
 fn test_resolve_consistency() {
@@ -315,3 +377,56 @@ fn test_resolve_consistency() {
 
     assert_eq!(first, second);
 }
+
+/// Lock down the resolved install order for the sample module graph via a
+/// checked-in snapshot. Run with `S1BCR4FT_BLESS=1` to regenerate after an
+/// intentional change.
+#[test]
+fn test_snapshot_resolved_install_order() {
+    let fixture = ModuleFixture::with_sample_modules();
+
+    let mut registry = ModuleRegistry::new(fixture.path());
+    registry.load_all().unwrap();
+
+    let resolver = ModuleResolver::new(&registry);
+    let resolved = resolver
+        .resolve(&["red-team/c2-frameworks/sliver-c2".to_string()])
+        .expect("Failed to resolve dependencies");
+
+    assert::snapshot("module_resolve_order", &resolved.join("\n"));
+}
+
+/// Lock down the circular dependency error message via a checked-in snapshot
+#[test]
+fn test_snapshot_circular_dependency_error() {
+    let fixture = ModuleFixture::with_circular_deps();
+
+    let mut registry = ModuleRegistry::new(fixture.path());
+    registry.load_all().unwrap();
+
+    let resolver = ModuleResolver::new(&registry);
+    let error = resolver
+        .resolve(&["circular/a".to_string()])
+        .unwrap_err()
+        .to_string();
+
+    assert::snapshot("module_circular_dependency_error", &error);
+}
+
+/// Lock down the conflict report between conflict/module-a and module-b via
+/// a checked-in snapshot
+#[test]
+fn test_snapshot_conflict_report() {
+    let fixture = ModuleFixture::with_sample_modules();
+
+    let mut registry = ModuleRegistry::new(fixture.path());
+    registry.load_all().unwrap();
+
+    let resolver = ModuleResolver::new(&registry);
+    let error = resolver
+        .check_conflicts(&["conflict/module-a".to_string(), "conflict/module-b".to_string()])
+        .unwrap_err()
+        .to_string();
+
+    assert::snapshot("module_conflict_report", &error);
+}
@@ -0,0 +1,47 @@
+//! Integration tests for package operations
+//!
+//! These run the same assertions against both the fast `PacmanMock` and,
+//! when a container runtime is available, a real `pacman` inside a
+//! throwaway Arch Linux container. Test bodies don't change between the two
+//! backends - only which `PackageManager` impl they're handed.
+
+mod common;
+
+use common::{ContainerPackageManager, PacmanMock, PackageManager};
+
+/// Exercises the subset of behavior every `PackageManager` impl must support
+fn run_install_suite(pm: &dyn PackageManager, installable_package: &str) {
+    assert!(pm.list_installed().is_empty() || !pm.is_installed(installable_package));
+
+    pm.install(installable_package)
+        .expect("Install should succeed");
+    assert!(pm.is_installed(installable_package));
+
+    let installed = pm.install_packages(&[installable_package]).unwrap();
+    assert_eq!(installed, vec![installable_package.to_string()]);
+}
+
+#[test]
+fn test_pacman_mock_implements_package_manager() {
+    let mock = PacmanMock::new();
+    run_install_suite(&mock, "vim");
+}
+
+#[test]
+fn test_pacman_mock_rejects_unknown_package() {
+    let mock = PacmanMock::new();
+    assert!(mock.install("not-a-real-package").is_err());
+}
+
+/// Same assertions as `test_pacman_mock_implements_package_manager`, run
+/// against a real container when Docker or Podman is available. Skips
+/// (rather than failing) when neither is installed.
+#[test]
+fn test_container_package_manager_implements_package_manager() {
+    let Some(container) = ContainerPackageManager::try_new() else {
+        eprintln!("Skipping: no Docker/Podman runtime available");
+        return;
+    };
+
+    run_install_suite(&container, "vim");
+}